@@ -3,6 +3,7 @@
 using System.IO;
 using FluentAssertions;
 using Spade;
+using Spade.Advanced.Interpolation;
 using Spade.Handles;
 using Spade.Primitives;
 using Xunit;
@@ -25,7 +26,7 @@ public PointWithValue(Point2<double> position, double value, int index)
         }
     }
 
-    [Fact(Skip = "Oracle NNI JSON not yet provided; generate via Julia/Rust and remove Skip to enable.")]
+    [Fact]
     public void NaturalNeighbor_MatchesOracleValuesAndWeights_WhenOracleAvailable()
     {
         var repoRoot = FindRepoRoot();
@@ -88,6 +89,160 @@ public void NaturalNeighbor_MatchesOracleValuesAndWeights_WhenOracleAvailable()
         }
     }
 
+    [Fact]
+    public void NaturalNeighbor_MatchesOracleGradientsAndSmoothValues()
+    {
+        var repoRoot = FindRepoRoot();
+        var oraclePath = Path.Combine(
+            repoRoot,
+            "oracle-tools",
+            "nni-oracle",
+            "gradient_case.json");
+
+        var oracle = OracleNniJson.ReadFromFile(oraclePath);
+        oracle.Flatness.Should().NotBeNull();
+        oracle.Gradients.Should().NotBeNull();
+
+        var triangulation = new DelaunayTriangulation<PointWithValue, int, int, int, LastUsedVertexHintGenerator<double>>();
+        for (int i = 0; i < oracle.Points.Count; i++)
+        {
+            var p = oracle.Points[i];
+            var v = oracle.Values[i];
+            triangulation.Insert(new PointWithValue(new Point2<double>(p.X, p.Y), v, i));
+        }
+
+        var nn = triangulation.NaturalNeighbor();
+        Func<VertexHandle<PointWithValue, int, int, int>, double> value = v => ((PointWithValue)v.Data).Value;
+        var gradients = nn.EstimateGradients(value);
+
+        // Compare estimated gradients (by point index).
+        var expectedByIndex = new Dictionary<int, OracleNniGradient>();
+        foreach (var entry in oracle.Gradients!)
+        {
+            expectedByIndex[entry.PointIndex] = entry;
+        }
+
+        foreach (var vertex in triangulation.Vertices())
+        {
+            var index = ((PointWithValue)vertex.Data).Index;
+            expectedByIndex.Should().ContainKey(index);
+            var actual = gradients(vertex);
+            actual.X.Should().BeApproximately(expectedByIndex[index].X, 1e-6);
+            actual.Y.Should().BeApproximately(expectedByIndex[index].Y, 1e-6);
+        }
+
+        // Compare C1 interpolated values.
+        foreach (var q in oracle.Queries)
+        {
+            var pos = new Point2<double>(q.X, q.Y);
+            var smooth = nn.InterpolateGradient(value, gradients, oracle.Flatness!.Value, pos);
+            q.SmoothValue.Should().NotBeNull();
+            smooth.Should().NotBeNull();
+            smooth!.Value.Should().BeApproximately(q.SmoothValue!.Value, 1e-6);
+        }
+    }
+
+    [Fact]
+    public void Barycentric_MatchesOracleValuesAndWeights()
+    {
+        var repoRoot = FindRepoRoot();
+        var oraclePath = Path.Combine(
+            repoRoot,
+            "oracle-tools",
+            "nni-oracle",
+            "barycentric_case.json");
+
+        var oracle = OracleNniJson.ReadBarycentricFromFile(oraclePath);
+
+        var triangulation = new DelaunayTriangulation<PointWithValue, int, int, int, LastUsedVertexHintGenerator<double>>();
+        for (int i = 0; i < oracle.Points.Count; i++)
+        {
+            var p = oracle.Points[i];
+            var v = oracle.Values[i];
+            triangulation.Insert(new PointWithValue(new Point2<double>(p.X, p.Y), v, i));
+        }
+
+        var barycentric = triangulation.Barycentric();
+
+        foreach (var q in oracle.Queries)
+        {
+            var pos = new Point2<double>(q.X, q.Y);
+
+            var weights = new List<(FixedVertexHandle Vertex, double Weight)>();
+            barycentric.GetWeights(pos, weights);
+            weights.Should().HaveCount(q.Weights.Count, "query ({0}, {1}) is {2}", q.X, q.Y, q.Location.Kind);
+
+            var value = barycentric.Interpolate(v => ((PointWithValue)v.Data).Value, pos);
+            if (q.Value is null)
+            {
+                value.Should().BeNull();
+                continue;
+            }
+
+            value.Should().NotBeNull();
+            value!.Value.Should().BeApproximately(q.Value.Value, 1e-9);
+
+            var actualByIndex = new Dictionary<int, double>();
+            foreach (var (vertex, w) in weights)
+            {
+                actualByIndex[((PointWithValue)triangulation.Vertex(vertex).Data).Index] = w;
+            }
+
+            foreach (var entry in q.Weights)
+            {
+                actualByIndex.Should().ContainKey(entry.PointIndex);
+                actualByIndex[entry.PointIndex].Should().BeApproximately(entry.Weight, 1e-9);
+            }
+        }
+    }
+
+    [Fact]
+    public void ExactGrid_MatchesOracleRaster()
+    {
+        var repoRoot = FindRepoRoot();
+        var oraclePath = Path.Combine(
+            repoRoot,
+            "oracle-tools",
+            "nni-oracle",
+            "raster_case.json");
+
+        var oracle = OracleNniJson.ReadRasterFromFile(oraclePath);
+
+        var samplePoints = new List<Point2<double>>();
+        var sampleValues = new List<double>();
+        for (int i = 0; i < oracle.Points.Count; i++)
+        {
+            samplePoints.Add(new Point2<double>(oracle.Points[i].X, oracle.Points[i].Y));
+            sampleValues.Add(oracle.Values[i]);
+        }
+
+        var grid = NaturalNeighborGrid2D.Exact(
+            samplePoints,
+            sampleValues,
+            oracle.Grid.Width,
+            oracle.Grid.Height,
+            new Point2<double>(oracle.Min.X, oracle.Min.Y),
+            new Point2<double>(oracle.Max.X, oracle.Max.Y));
+
+        grid.GetLength(0).Should().Be(oracle.Rows.Count);
+        for (int iy = 0; iy < oracle.Rows.Count; iy++)
+        {
+            grid.GetLength(1).Should().Be(oracle.Rows[iy].Count);
+            for (int ix = 0; ix < oracle.Rows[iy].Count; ix++)
+            {
+                var expected = oracle.Rows[iy][ix];
+                if (expected is null)
+                {
+                    double.IsNaN(grid[iy, ix]).Should().BeTrue("node ({0}, {1}) is outside the hull", ix, iy);
+                }
+                else
+                {
+                    grid[iy, ix].Should().BeApproximately(expected.Value, 1e-9);
+                }
+            }
+        }
+    }
+
     private static string FindRepoRoot()
     {
         var dir = AppContext.BaseDirectory;
@@ -19,14 +19,26 @@ internal sealed record OracleNniWeightEntry(
     [property: JsonPropertyName("pointIndex")] int PointIndex,
     [property: JsonPropertyName("weight")] double Weight);
 
+/// <summary>
+/// The estimated gradient of the vertex created by a given input point.
+/// </summary>
+internal sealed record OracleNniGradient(
+    [property: JsonPropertyName("pointIndex")] int PointIndex,
+    [property: JsonPropertyName("x")] double X,
+    [property: JsonPropertyName("y")] double Y);
+
 /// <summary>
 /// Oracle description of a single query: location, weights, and interpolated value.
 /// </summary>
+/// <remarks>
+/// <see cref="SmoothValue"/> is only present when the oracle was run with a flatness.
+/// </remarks>
 internal sealed record OracleNniQueryOutput(
     [property: JsonPropertyName("x")] double X,
     [property: JsonPropertyName("y")] double Y,
     [property: JsonPropertyName("weights")] IReadOnlyList<OracleNniWeightEntry> Weights,
-    [property: JsonPropertyName("value")] double Value);
+    [property: JsonPropertyName("value")] double Value,
+    [property: JsonPropertyName("smoothValue")] double? SmoothValue);
 
 /// <summary>
 /// Oracle natural neighbor output for a given point set and value field.
@@ -36,11 +48,14 @@ internal sealed record OracleNniQueryOutput(
 /// {
 ///   "points":  [ { "x": ..., "y": ... }, ... ],
 ///   "values":  [ v0, v1, ... ],
+///   "flatness": 0.5,                                      // optional
+///   "gradients": [ { "pointIndex": 0, "x": ..., "y": ... }, ... ], // optional
 ///   "queries": [
 ///     {
 ///       "x": ..., "y": ...,
 ///       "weights": [ { "pointIndex": 0, "weight": 0.25 }, ... ],
-///       "value": ...
+///       "value": ...,
+///       "smoothValue": ...                                // optional
 ///     },
 ///     ...
 ///   ]
@@ -49,8 +64,63 @@ internal sealed record OracleNniQueryOutput(
 internal sealed record OracleNniOutput(
     [property: JsonPropertyName("points")] IReadOnlyList<OracleNniPoint> Points,
     [property: JsonPropertyName("values")] IReadOnlyList<double> Values,
+    [property: JsonPropertyName("flatness")] double? Flatness,
+    [property: JsonPropertyName("gradients")] IReadOnlyList<OracleNniGradient>? Gradients,
     [property: JsonPropertyName("queries")] IReadOnlyList<OracleNniQueryOutput> Queries);
 
+/// <summary>
+/// Where the oracle located a barycentric query; the same shape as the <c>locate</c> output,
+/// with vertices given by input index.
+/// </summary>
+internal sealed record OracleBarycentricLocation(
+    [property: JsonPropertyName("kind")] string Kind);
+
+/// <summary>
+/// Oracle description of a single barycentric query.
+/// </summary>
+/// <remarks>
+/// Outside the convex hull <see cref="Weights"/> is empty and <see cref="Value"/> is absent.
+/// </remarks>
+internal sealed record OracleBarycentricQueryOutput(
+    [property: JsonPropertyName("x")] double X,
+    [property: JsonPropertyName("y")] double Y,
+    [property: JsonPropertyName("location")] OracleBarycentricLocation Location,
+    [property: JsonPropertyName("weights")] IReadOnlyList<OracleNniWeightEntry> Weights,
+    [property: JsonPropertyName("value")] double? Value);
+
+/// <summary>
+/// Oracle barycentric interpolation output; same shape as <see cref="OracleNniOutput"/> plus a
+/// <c>location</c> per query whose <c>kind</c> is <c>onVertex</c>, <c>onEdge</c>, <c>onFace</c>,
+/// <c>outsideOfConvexHull</c> or <c>noTriangulation</c>.
+/// </summary>
+internal sealed record OracleBarycentricOutput(
+    [property: JsonPropertyName("points")] IReadOnlyList<OracleNniPoint> Points,
+    [property: JsonPropertyName("values")] IReadOnlyList<double> Values,
+    [property: JsonPropertyName("queries")] IReadOnlyList<OracleBarycentricQueryOutput> Queries);
+
+/// <summary>
+/// Grid specification of a raster oracle: node (ix, iy) is at origin + (ix, iy) * cellSize.
+/// </summary>
+internal sealed record OracleRasterGrid(
+    [property: JsonPropertyName("origin")] OracleNniPoint Origin,
+    [property: JsonPropertyName("cellSize")] double CellSize,
+    [property: JsonPropertyName("width")] int Width,
+    [property: JsonPropertyName("height")] int Height);
+
+/// <summary>
+/// Oracle exact natural neighbor raster of the samples <see cref="Points"/> and
+/// <see cref="Values"/>. <c>rows[iy][ix]</c> matches <c>grid[iy, ix]</c> of
+/// <c>NaturalNeighborGrid2D</c> called with <see cref="Min"/> and <see cref="Max"/>; nodes
+/// outside the convex hull are <c>null</c>.
+/// </summary>
+internal sealed record OracleRasterOutput(
+    [property: JsonPropertyName("points")] IReadOnlyList<OracleNniPoint> Points,
+    [property: JsonPropertyName("values")] IReadOnlyList<double> Values,
+    [property: JsonPropertyName("grid")] OracleRasterGrid Grid,
+    [property: JsonPropertyName("min")] OracleNniPoint Min,
+    [property: JsonPropertyName("max")] OracleNniPoint Max,
+    [property: JsonPropertyName("rows")] IReadOnlyList<IReadOnlyList<double?>> Rows);
+
 internal static class OracleNniJson
 {
     private static readonly JsonSerializerOptions Options = new()
@@ -64,4 +134,16 @@ public static OracleNniOutput ReadFromFile(string path)
         var json = File.ReadAllText(path);
         return JsonSerializer.Deserialize<OracleNniOutput>(json, Options)!;
     }
+
+    public static OracleBarycentricOutput ReadBarycentricFromFile(string path)
+    {
+        var json = File.ReadAllText(path);
+        return JsonSerializer.Deserialize<OracleBarycentricOutput>(json, Options)!;
+    }
+
+    public static OracleRasterOutput ReadRasterFromFile(string path)
+    {
+        var json = File.ReadAllText(path);
+        return JsonSerializer.Deserialize<OracleRasterOutput>(json, Options)!;
+    }
 }
@@ -28,16 +28,38 @@ internal sealed record OracleTriangulationOutput(
 internal sealed record OracleVoronoiCell(
     [property: JsonPropertyName("generatorIndex")] int GeneratorIndex,
     [property: JsonPropertyName("polygon")] IReadOnlyList<OraclePoint> Polygon,
-    [property: JsonPropertyName("neighbors")] IReadOnlyList<int> Neighbors);
+    [property: JsonPropertyName("neighbors")] IReadOnlyList<int> Neighbors,
+    [property: JsonPropertyName("bounded")] bool Bounded = true,
+    [property: JsonPropertyName("rays")] IReadOnlyList<OracleVoronoiRay>? Rays = null);
+
+internal sealed record OracleVoronoiRay(
+    [property: JsonPropertyName("origin")] OraclePoint Origin,
+    [property: JsonPropertyName("direction")] OraclePoint Direction);
+
+/// <summary>
+/// A Voronoi edge as written by the oracle's <c>voronoi</c> command. <see cref="Kind"/> is
+/// <c>segment</c> (<see cref="From"/> and <see cref="To"/> set), <c>ray</c> or <c>line</c>
+/// (<see cref="Origin"/> and <see cref="Direction"/> set). <see cref="Generators"/> holds the
+/// oracle point index of the cell on the edge's left, then the one on its right.
+/// </summary>
+internal sealed record OracleVoronoiEdge(
+    [property: JsonPropertyName("generators")] int[] Generators,
+    [property: JsonPropertyName("kind")] string Kind,
+    [property: JsonPropertyName("from")] OraclePoint? From = null,
+    [property: JsonPropertyName("to")] OraclePoint? To = null,
+    [property: JsonPropertyName("origin")] OraclePoint? Origin = null,
+    [property: JsonPropertyName("direction")] OraclePoint? Direction = null);
 
 internal sealed record OracleVoronoiOutput(
-    [property: JsonPropertyName("cells")] IReadOnlyList<OracleVoronoiCell> Cells);
+    [property: JsonPropertyName("cells")] IReadOnlyList<OracleVoronoiCell> Cells,
+    [property: JsonPropertyName("edges")] IReadOnlyList<OracleVoronoiEdge>? Edges = null);
 
 internal static class OracleJson
 {
     private static readonly JsonSerializerOptions Options = new()
     {
         PropertyNamingPolicy = JsonNamingPolicy.CamelCase,
+        NumberHandling = JsonNumberHandling.AllowNamedFloatingPointLiterals,
         WriteIndented = true,
     };
 
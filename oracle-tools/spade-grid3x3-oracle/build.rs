@@ -0,0 +1,31 @@
+//! Exposes the resolved spade version as `SPADE_VERSION`, so batch manifests record which spade
+//! produced them. Falls back to `unknown` when no lockfile is found.
+
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+fn main() {
+    let manifest_dir = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap());
+    let version = manifest_dir
+        .ancestors()
+        .map(|dir| dir.join("Cargo.lock"))
+        .find(|path| path.is_file())
+        .and_then(|path| {
+            println!("cargo:rerun-if-changed={}", path.display());
+            spade_version(&fs::read_to_string(path).ok()?)
+        })
+        .unwrap_or_else(|| "unknown".to_string());
+
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rustc-env=SPADE_VERSION={version}");
+}
+
+fn spade_version(lock: &str) -> Option<String> {
+    lock.split("[[package]]").find_map(|package| {
+        let mut lines = package.lines().map(str::trim);
+        lines.find(|line| *line == "name = \"spade\"")?;
+        let version = lines.find_map(|line| line.strip_prefix("version = "))?;
+        Some(version.trim_matches('"').to_string())
+    })
+}
@@ -0,0 +1,80 @@
+//! Barycentric interpolation with spade's `Barycentric`, next to where each query was located.
+//!
+//! Spade locates the query and weighs the three corners of the face it lies on, the two ends of
+//! the edge it lies on (by distance along the edge) or the single vertex it hits. Outside the
+//! convex hull, or with no vertices at all, there are no weights and no value. Vertex values are
+//! resolved like in `nni`: a merged duplicate's value overrides the earlier one.
+
+use std::error::Error;
+
+use serde::Serialize;
+use spade::{DelaunayTriangulation, FloatTriangulation, Point2};
+
+use crate::locate::{locate, OracleLocation};
+use crate::model::{OracleInput, OraclePoint};
+use crate::nni::{vertex_values, OracleNniWeight};
+use crate::vertex_index::VertexIndex;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OracleBarycentricOutput {
+    pub points: Vec<OraclePoint>,
+    pub values: Vec<f64>,
+    pub queries: Vec<OracleBarycentricQuery>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OracleBarycentricQuery {
+    pub x: f64,
+    pub y: f64,
+    /// `locate` of the query, in input-index terms as in the `locate` output.
+    pub location: OracleLocation,
+    /// One weight per face corner, edge end or hit vertex, in the order spade returns them;
+    /// empty outside the convex hull.
+    pub weights: Vec<OracleNniWeight>,
+    /// Absent outside the convex hull, where spade returns `None`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<f64>,
+}
+
+pub fn barycentric(
+    triangulation: &DelaunayTriangulation<Point2<f64>>,
+    index: &VertexIndex,
+    input: &OracleInput,
+) -> Result<OracleBarycentricOutput, Box<dyn Error>> {
+    if input.values.is_empty() && !input.points.is_empty() {
+        return Err("barycentric needs one `values` entry per point".into());
+    }
+    let vertex_values = vertex_values(triangulation, index, &input.values);
+
+    let interpolator = triangulation.barycentric();
+    let mut weights = Vec::new();
+    let queries = input
+        .queries
+        .iter()
+        .map(|q| {
+            let position = Point2::new(q.x, q.y);
+            interpolator.get_weights(position, &mut weights);
+            OracleBarycentricQuery {
+                x: q.x,
+                y: q.y,
+                location: locate(triangulation, index, position),
+                weights: weights
+                    .iter()
+                    .map(|&(vertex, weight)| OracleNniWeight {
+                        point_index: index.input_index(vertex),
+                        weight,
+                    })
+                    .collect(),
+                value: interpolator.interpolate(|v| vertex_values[v.fix().index()], position),
+            }
+        })
+        .collect();
+
+    Ok(OracleBarycentricOutput {
+        points: input.points.clone(),
+        values: input.values.clone(),
+        queries,
+    })
+}
@@ -0,0 +1,265 @@
+//! Runs one command over every `*.json` input below a directory.
+//!
+//! Each input `<input_dir>/a/b.json` produces `<output_dir>/a/b.json`, and `manifest.json` in the
+//! output directory lists every case so the .NET tests can enumerate it as `[Theory]` data. A case
+//! that fails is recorded in the manifest and the batch carries on.
+
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use clap::ValueEnum;
+use serde::Serialize;
+
+use crate::barycentric::barycentric;
+use crate::cdt::{build_cdt, cdt_output};
+use crate::clipped_voronoi::clipped_voronoi;
+use crate::dcel::dump_dcel;
+use crate::locate::locate_queries;
+use crate::model::OracleInput;
+use crate::nearest::nearest_neighbors;
+use crate::nni::natural_neighbor;
+use crate::quality::{quality_report, QualitySource};
+use crate::raster::raster;
+use crate::refine::refine;
+use crate::report::report;
+use crate::trace::trace_insertions;
+use crate::voronoi::voronoi_output;
+use crate::{build_delaunay, to_json, triangle_output};
+
+pub const MANIFEST_FILE: &str = "manifest.json";
+
+/// The per-case command; each writes the same JSON as the subcommand of the same name
+/// (`cdt-split` is `cdt --split`, `quality` is `quality --format json` with the default `--of`
+/// and `--bins`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum BatchMode {
+    Triangulate,
+    Cdt,
+    CdtSplit,
+    Refine,
+    Quality,
+    Voronoi,
+    ClippedVoronoi,
+    Nni,
+    Barycentric,
+    Raster,
+    Locate,
+    Nearest,
+    Dcel,
+    Trace,
+    Report,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OracleBatchManifest {
+    pub spade_version: String,
+    pub mode: BatchMode,
+    pub cases: Vec<OracleBatchCase>,
+}
+
+/// Paths are relative to the input and output directories, with `/` separators.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OracleBatchCase {
+    pub name: String,
+    pub input: String,
+    /// Absent when the case failed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output: Option<String>,
+    /// FNV-1a 64 of the input file's bytes, as 16 lowercase hex digits.
+    pub hash: String,
+    /// Absent when the input could not be parsed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub point_count: Option<usize>,
+    pub status: BatchStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum BatchStatus {
+    Ok,
+    InvalidInput,
+    Failed,
+}
+
+pub fn run_batch(
+    input_dir: &Path,
+    output_dir: &Path,
+    mode: BatchMode,
+) -> Result<OracleBatchManifest, Box<dyn Error>> {
+    // Every output would overwrite its own input. An output directory that does not exist yet
+    // cannot be the input directory.
+    if output_dir.canonicalize().ok() == Some(input_dir.canonicalize()?) {
+        return Err(format!(
+            "the output directory {} is the input directory",
+            output_dir.display()
+        )
+        .into());
+    }
+
+    let mut inputs = Vec::new();
+    collect_inputs(input_dir, output_dir, &mut inputs)?;
+    inputs.sort();
+
+    let mut cases = Vec::with_capacity(inputs.len());
+    for path in inputs {
+        let relative = path.strip_prefix(input_dir)?;
+        let bytes = fs::read(&path)?;
+        let mut case = OracleBatchCase {
+            name: slash_path(&relative.with_extension("")),
+            input: slash_path(relative),
+            output: None,
+            hash: format!("{:016x}", fnv1a64(&bytes)),
+            point_count: None,
+            status: BatchStatus::Ok,
+            error: None,
+        };
+
+        let input = match std::str::from_utf8(&bytes)
+            .map_err(Box::<dyn Error>::from)
+            .and_then(|json| OracleInput::parse(json, &path))
+        {
+            Ok(input) => input,
+            Err(e) => {
+                case.status = BatchStatus::InvalidInput;
+                case.error = Some(e.to_string());
+                cases.push(case);
+                continue;
+            }
+        };
+        case.point_count = Some(input.points.len());
+
+        match render(mode, &input) {
+            Ok(rendered) => {
+                let target = output_dir.join(relative);
+                if let Some(parent) = target.parent() {
+                    fs::create_dir_all(parent)?;
+                }
+                fs::write(&target, rendered)?;
+                case.output = Some(case.input.clone());
+            }
+            Err(e) => {
+                case.status = BatchStatus::Failed;
+                case.error = Some(e.to_string());
+            }
+        }
+        cases.push(case);
+    }
+
+    let manifest = OracleBatchManifest {
+        spade_version: env!("SPADE_VERSION").to_string(),
+        mode,
+        cases,
+    };
+    fs::create_dir_all(output_dir)?;
+    fs::write(output_dir.join(MANIFEST_FILE), to_json(&manifest)?)?;
+    Ok(manifest)
+}
+
+fn render(mode: BatchMode, input: &OracleInput) -> Result<String, Box<dyn Error>> {
+    let json = match mode {
+        BatchMode::Triangulate => {
+            let (triangulation, index) = build_delaunay(input);
+            to_json(&triangle_output(&triangulation, &index, &input.points))?
+        }
+        BatchMode::Cdt | BatchMode::CdtSplit => {
+            to_json(&cdt_output(build_cdt(input, mode == BatchMode::CdtSplit)))?
+        }
+        BatchMode::Refine => to_json(&refine(input))?,
+        BatchMode::Quality => to_json(&quality_report(input, QualitySource::Triangulate, 10))?,
+        BatchMode::Voronoi => {
+            let (triangulation, index) = build_delaunay(input);
+            to_json(&voronoi_output(&triangulation, &index, &input.points))?
+        }
+        BatchMode::ClippedVoronoi => {
+            let (triangulation, index) = build_delaunay(input);
+            let domain = input.domain.as_ref();
+            to_json(&clipped_voronoi(
+                &triangulation,
+                &index,
+                &input.points,
+                domain,
+            )?)?
+        }
+        BatchMode::Nni => {
+            let (triangulation, index) = build_delaunay(input);
+            to_json(&natural_neighbor(&triangulation, &index, input, None)?)?
+        }
+        BatchMode::Barycentric => {
+            let (triangulation, index) = build_delaunay(input);
+            to_json(&barycentric(&triangulation, &index, input)?)?
+        }
+        BatchMode::Raster => {
+            let (triangulation, index) = build_delaunay(input);
+            to_json(&raster(&triangulation, &index, input)?)?
+        }
+        BatchMode::Locate => {
+            let (triangulation, index) = build_delaunay(input);
+            to_json(&locate_queries(
+                &triangulation,
+                &index,
+                &input.points,
+                &input.queries,
+            ))?
+        }
+        BatchMode::Nearest => {
+            let (triangulation, index) = build_delaunay(input);
+            to_json(&nearest_neighbors(
+                &triangulation,
+                &index,
+                &input.points,
+                &input.queries,
+            ))?
+        }
+        BatchMode::Dcel => {
+            let (triangulation, index) = build_delaunay(input);
+            to_json(&dump_dcel(&triangulation, &index, &input.points))?
+        }
+        BatchMode::Trace => to_json(&trace_insertions(&input.points))?,
+        BatchMode::Report => {
+            let (triangulation, index) = build_delaunay(input);
+            to_json(&report(&triangulation, &index, &input.points))?
+        }
+    };
+    Ok(json)
+}
+
+/// Recursively collects `*.json` files, skipping `output_dir` in case it is nested in the input.
+fn collect_inputs(dir: &Path, output_dir: &Path, inputs: &mut Vec<PathBuf>) -> std::io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            if !same_path(&path, output_dir) {
+                collect_inputs(&path, output_dir, inputs)?;
+            }
+        } else if path.extension().is_some_and(|e| e == "json") {
+            inputs.push(path);
+        }
+    }
+    Ok(())
+}
+
+fn same_path(a: &Path, b: &Path) -> bool {
+    match (a.canonicalize(), b.canonicalize()) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => false,
+    }
+}
+
+fn slash_path(path: &Path) -> String {
+    path.components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn fnv1a64(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &b| {
+        (hash ^ u64::from(b)).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
@@ -0,0 +1,352 @@
+//! Constrained Delaunay triangulation of an `OracleInput` with `constraints`.
+//!
+//! Points are inserted in input order first, then every constraint is added in input order with
+//! `add_constraint`, mirroring the .NET `AddConstraint`. Spade panics when a constraint crosses an
+//! existing one, so such constraints are checked with `can_add_constraint` and skipped instead.
+//!
+//! In split mode constraints are added with `add_constraint_and_split` (the .NET
+//! `AddConstraintWithSplitting`) instead, which splits crossed constraints at Steiner vertices.
+//! Steiner vertices are numbered after the input points, in the order spade creates them, and are
+//! appended to the output `points` so triangle and edge indices stay valid.
+//!
+//! A polygon domain adds its rings as constraint loops; see [`crate::domain`].
+
+use std::fmt::Write as _;
+
+use serde::Serialize;
+use spade::handles::FixedVertexHandle;
+use spade::{ConstrainedDelaunayTriangulation, Point2, Triangulation};
+
+use crate::domain::{
+    classify_faces, domain_rings, ring_edges, OracleDomainOutput, OracleDomainRing,
+};
+use crate::model::{OracleInput, OraclePoint, OracleTriangulationOutput};
+use crate::vertex_index::{insert_points, VertexIndex};
+use crate::{render_triangles_text, triangle_output};
+
+pub type Cdt = ConstrainedDelaunayTriangulation<Point2<f64>>;
+
+/// A superset of `OracleTriangulationOutput`, so `OracleJson.DeserializeTriangulation` still
+/// reads the triangles.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OracleCdtOutput {
+    #[serde(flatten)]
+    pub triangulation: OracleTriangulationOutput,
+    /// Every undirected edge as a sorted pair of input indices, in sorted order.
+    pub edges: Vec<OracleCdtEdge>,
+    /// Outcome of every input constraint in input order, followed by the domain ring edges.
+    pub constraints: Vec<OracleConstraintResult>,
+    /// Vertices created by splitting constraints; also appended to `points`.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub steiner_points: Vec<OracleSteinerPoint>,
+    /// Present when the input has a polygon domain.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub domain: Option<OracleDomainOutput>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OracleCdtEdge {
+    pub vertices: [usize; 2],
+    pub constraint: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OracleConstraintResult {
+    /// Index into the input `constraints`.
+    pub index: usize,
+    pub from: usize,
+    pub to: usize,
+    pub status: OracleConstraintStatus,
+    /// Split mode only: the directed constraint edges from `from` to `to` in the final mesh,
+    /// through the Steiner vertices of this and any later constraint that crossed it.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sub_constraints: Option<Vec<[usize; 2]>>,
+    /// Split mode only: indices of the Steiner vertices this constraint created.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub steiner: Vec<usize>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OracleSteinerPoint {
+    pub index: usize,
+    pub x: f64,
+    pub y: f64,
+    /// Index of the input constraint whose insertion created this vertex.
+    pub constraint: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum OracleConstraintStatus {
+    /// At least one new constraint edge was created (`add_constraint` returned `true`).
+    Added,
+    /// No new constraint edge was created: every edge along the way already was a constraint,
+    /// or both ends resolved to the same vertex.
+    Unchanged,
+    /// `can_add_constraint` returned `false`, so the constraint was skipped.
+    IntersectsConstraint,
+    /// An endpoint was rejected by spade and has no vertex.
+    RejectedVertex,
+}
+
+/// A built CDT together with everything needed to report it.
+pub struct CdtBuild {
+    pub cdt: Cdt,
+    pub index: VertexIndex,
+    /// Input points followed by the domain ring vertices.
+    pub points: Vec<OraclePoint>,
+    pub constraints: Vec<OracleConstraintResult>,
+    pub rings: Vec<OracleDomainRing>,
+}
+
+pub fn build_cdt(input: &OracleInput, split: bool) -> CdtBuild {
+    let (ring_points, rings) = domain_rings(input.domain.as_ref(), input.points.len());
+    let mut points = input.points.clone();
+    points.extend(ring_points);
+    let constraints: Vec<[usize; 2]> = input
+        .constraints
+        .iter()
+        .copied()
+        .chain(rings.iter().flat_map(ring_edges))
+        .collect();
+
+    let mut cdt = Cdt::new();
+    let mut index = insert_points(&mut cdt, &points);
+
+    let mut results = Vec::with_capacity(constraints.len());
+    // Split mode only: the vertices along every constraint so far, kept up to date as later
+    // constraints split it.
+    let mut chains: Vec<Vec<FixedVertexHandle>> = Vec::new();
+    for (i, [from, to]) in constraints.into_iter().enumerate() {
+        let mut result = OracleConstraintResult {
+            index: i,
+            from,
+            to,
+            status: OracleConstraintStatus::RejectedVertex,
+            sub_constraints: None,
+            steiner: Vec::new(),
+        };
+        let mut chain = Vec::new();
+
+        if let (Some(a), Some(b)) = (index.vertex(from), index.vertex(to)) {
+            if split {
+                let initial_constraints = cdt.num_constraints();
+                let first_steiner = index.vertex_count();
+                let edges = cdt.add_constraint_and_split(a, b, |p| p);
+                for v in first_steiner..cdt.num_vertices() {
+                    result
+                        .steiner
+                        .push(index.record_steiner(FixedVertexHandle::from_index(v)));
+                }
+                let steiner: Vec<_> = (first_steiner..cdt.num_vertices())
+                    .map(FixedVertexHandle::from_index)
+                    .collect();
+                for earlier in &mut chains {
+                    split_chain(&cdt, earlier, &steiner);
+                }
+
+                if let Some(first) = edges.first() {
+                    chain.push(cdt.directed_edge(*first).from().fix());
+                }
+                chain.extend(edges.iter().map(|&e| cdt.directed_edge(e).to().fix()));
+                result.sub_constraints = Some(Vec::new());
+                result.status = if cdt.num_constraints() != initial_constraints {
+                    OracleConstraintStatus::Added
+                } else {
+                    OracleConstraintStatus::Unchanged
+                };
+            } else if !cdt.can_add_constraint(a, b) {
+                result.status = OracleConstraintStatus::IntersectsConstraint;
+            } else if cdt.add_constraint(a, b) {
+                result.status = OracleConstraintStatus::Added;
+            } else {
+                result.status = OracleConstraintStatus::Unchanged;
+            }
+        }
+        chains.push(chain);
+        results.push(result);
+    }
+
+    for (result, chain) in results.iter_mut().zip(&chains) {
+        if let Some(sub_constraints) = &mut result.sub_constraints {
+            *sub_constraints = chain
+                .windows(2)
+                .map(|pair| [index.input_index(pair[0]), index.input_index(pair[1])])
+                .collect();
+        }
+    }
+
+    CdtBuild {
+        cdt,
+        index,
+        points,
+        constraints: results,
+        rings,
+    }
+}
+
+/// Inserts every vertex of `steiner` that now splits a constraint edge of `chain` in two.
+fn split_chain(cdt: &Cdt, chain: &mut Vec<FixedVertexHandle>, steiner: &[FixedVertexHandle]) {
+    let is_constraint = |a, b| {
+        cdt.get_edge_from_neighbors(a, b)
+            .is_some_and(|edge| edge.is_constraint_edge())
+    };
+    let mut k = 0;
+    while k + 1 < chain.len() {
+        let (a, b) = (chain[k], chain[k + 1]);
+        if !is_constraint(a, b) {
+            if let Some(&s) = steiner
+                .iter()
+                .find(|&&s| is_constraint(a, s) && is_constraint(s, b))
+            {
+                chain.insert(k + 1, s);
+            }
+        }
+        k += 1;
+    }
+}
+
+pub fn cdt_output(build: CdtBuild) -> OracleCdtOutput {
+    let CdtBuild {
+        cdt,
+        index,
+        points,
+        constraints,
+        rings,
+    } = build;
+
+    let edges = cdt_edges(&cdt, &index);
+
+    // Steiner vertices are created after every input vertex, so they come last in handle order.
+    let steiner_points: Vec<OracleSteinerPoint> = cdt
+        .vertices()
+        .filter_map(|v| {
+            let i = index.input_index(v.fix());
+            let constraint = constraints.iter().find(|c| c.steiner.contains(&i))?;
+            let position = v.position();
+            Some(OracleSteinerPoint {
+                index: i,
+                x: position.x,
+                y: position.y,
+                constraint: constraint.index,
+            })
+        })
+        .collect();
+
+    let mut all_points = points;
+    all_points.extend(
+        steiner_points
+            .iter()
+            .map(|p| OraclePoint { x: p.x, y: p.y }),
+    );
+
+    let domain = (!rings.is_empty()).then(|| {
+        let (faces, flood_fill_matches_spade) = classify_faces(&cdt, &index);
+        OracleDomainOutput {
+            rings,
+            faces,
+            flood_fill_matches_spade,
+        }
+    });
+
+    OracleCdtOutput {
+        triangulation: triangle_output(&cdt, &index, &all_points),
+        edges,
+        constraints,
+        steiner_points,
+        domain,
+    }
+}
+
+/// Every undirected edge as a sorted pair of indices, in sorted order.
+pub fn cdt_edges(cdt: &Cdt, index: &VertexIndex) -> Vec<OracleCdtEdge> {
+    let mut edges: Vec<OracleCdtEdge> = cdt
+        .undirected_edges()
+        .map(|edge| {
+            let mut vertices = edge.vertices().map(|v| index.input_index(v.fix()));
+            vertices.sort();
+            OracleCdtEdge {
+                vertices,
+                constraint: edge.is_constraint_edge(),
+            }
+        })
+        .collect();
+    edges.sort();
+    edges
+}
+
+pub fn render_cdt_text(output: &OracleCdtOutput) -> String {
+    let mut text = render_triangles_text(&output.triangulation);
+    for e in output.edges.iter().filter(|e| e.constraint) {
+        let _ = writeln!(
+            text,
+            "constraint edge: {} - {}",
+            e.vertices[0], e.vertices[1]
+        );
+    }
+    for c in &output.constraints {
+        let _ = writeln!(
+            text,
+            "constraint {} ({} - {}): {:?}",
+            c.index, c.from, c.to, c.status
+        );
+    }
+    if let Some(domain) = &output.domain {
+        for f in &domain.faces {
+            let [a, b, c] = f.triangle;
+            let _ = writeln!(text, "face [{a}, {b}, {c}]: {:?}", f.region);
+        }
+    }
+    for p in &output.steiner_points {
+        let _ = writeln!(
+            text,
+            "steiner: {} at ({:?}, {:?}) from constraint {}",
+            p.index, p.x, p.y, p.constraint
+        );
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+
+    #[test]
+    fn later_splits_update_sub_constraints() {
+        let json = r#"{
+            "points": [
+                { "x": 0, "y": 0 }, { "x": 4, "y": 0 }, { "x": 4, "y": 4 }, { "x": 0, "y": 4 },
+                { "x": 1, "y": 0 }, { "x": 1, "y": 4 }
+            ],
+            "constraints": [[0, 2], [4, 5], [1, 3]]
+        }"#;
+        let input = OracleInput::parse(json, Path::new("crossing.json")).unwrap();
+        let output = cdt_output(build_cdt(&input, true));
+
+        let sub_constraints: Vec<_> = output
+            .constraints
+            .iter()
+            .map(|c| c.sub_constraints.clone().unwrap())
+            .collect();
+        // Steiner vertices: 6 at (1, 1) from constraint 1, then 7 at (2, 2) and 8 at (1, 3) from
+        // constraint 2.
+        assert_eq!(sub_constraints[0], [[0, 6], [6, 7], [7, 2]]);
+        assert_eq!(sub_constraints[1], [[4, 6], [6, 8], [8, 5]]);
+        assert_eq!(sub_constraints[2], [[1, 7], [7, 8], [8, 3]]);
+
+        for [a, b] in sub_constraints.into_iter().flatten() {
+            let mut vertices = [a, b];
+            vertices.sort();
+            assert!(output.edges.contains(&OracleCdtEdge {
+                vertices,
+                constraint: true,
+            }));
+        }
+    }
+}
@@ -0,0 +1,271 @@
+//! Voronoi cells clipped to the input domain polygon, as a reference for the .NET
+//! `ClippedVoronoiBuilder`.
+//!
+//! Like the .NET builder, the domain is clipped by the perpendicular bisector of every Delaunay
+//! edge around the generator, keeping the side the generator is on. The bisector is taken from
+//! the Delaunay edge itself rather than through the circumcenters at the Voronoi edge's ends:
+//! near-cocircular generators put those circumcenters within rounding of each other, so the line
+//! through them points anywhere. Sides are decided with the exact `orient2d` predicate; only the
+//! bisectors and intersection points are rounded.
+//!
+//! Like `ClipPolygon`, the domain must be convex; it may be given in either orientation and is
+//! reported counterclockwise. Holes are ignored. Generators are visited in vertex handle order,
+//! which is ascending input order, so `cells`, `degenerateCells` and `outsideDomain` are all
+//! sorted by generator index.
+
+use std::error::Error;
+
+use serde::Serialize;
+use spade::{Point2, Triangulation};
+
+use crate::model::{OracleDomain, OraclePoint, OracleTriangulationOutput};
+use crate::triangle_output;
+use crate::vertex_index::VertexIndex;
+use crate::voronoi::left_normal;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OracleClippedVoronoiOutput {
+    #[serde(flatten)]
+    pub triangulation: OracleTriangulationOutput,
+    /// The clip polygon, counterclockwise.
+    pub domain: Vec<OraclePoint>,
+    pub cells: Vec<OracleClippedVoronoiCell>,
+    /// Generators inside the domain whose clipped cell has fewer than 3 vertices.
+    pub degenerate_cells: Vec<usize>,
+    /// Generators strictly outside the domain; they get no cell.
+    pub outside_domain: Vec<usize>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OracleClippedVoronoiCell {
+    pub generator_index: usize,
+    /// Counterclockwise, without repeated points.
+    pub polygon: Vec<OraclePoint>,
+    /// Whether part of the cell's boundary comes from the domain, the .NET `IsClipped`.
+    pub clipped: bool,
+}
+
+/// Where a polygon edge came from: the domain boundary or a Voronoi edge's line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EdgeSource {
+    Domain,
+    Voronoi,
+}
+
+/// A polygon vertex and the source of the edge starting at it.
+pub type Corner = (Point2<f64>, EdgeSource);
+
+pub fn clipped_voronoi<T>(
+    triangulation: &T,
+    index: &VertexIndex,
+    points: &[OraclePoint],
+    domain: Option<&OracleDomain>,
+) -> Result<OracleClippedVoronoiOutput, Box<dyn Error>>
+where
+    T: Triangulation<Vertex = Point2<f64>>,
+{
+    let polygon = domain
+        .and_then(|d| d.polygon.as_ref())
+        .ok_or("clipped Voronoi needs a domain polygon")?;
+    let mut clip: Vec<Point2<f64>> = polygon
+        .vertices
+        .iter()
+        .map(|p| Point2::new(p.x, p.y))
+        .collect();
+    if signed_area(&clip) < 0.0 {
+        clip.reverse();
+    }
+    if !is_convex(&clip) {
+        return Err("clipped Voronoi needs a convex domain polygon".into());
+    }
+
+    let mut cells = Vec::new();
+    let mut degenerate_cells = Vec::new();
+    let mut outside_domain = Vec::new();
+    for generator in triangulation.vertices() {
+        let generator_index = index.input_index(generator.fix());
+        let site = generator.position();
+        if !contains(&clip, site) {
+            outside_domain.push(generator_index);
+            continue;
+        }
+
+        let mut cell: Vec<Corner> = clip.iter().map(|&p| (p, EdgeSource::Domain)).collect();
+        for edge in generator.out_edges() {
+            let [from, to] = edge.positions();
+            let a = Point2::new((from.x + to.x) / 2.0, (from.y + to.y) / 2.0);
+            let direction = left_normal(from, to);
+            let b = Point2::new(a.x + direction.x, a.y + direction.y);
+            cell = clip_to_side(&cell, a, b, orient(a, b, site).signum());
+            if cell.len() < 3 {
+                break;
+            }
+        }
+
+        if cell.len() < 3 {
+            degenerate_cells.push(generator_index);
+            continue;
+        }
+        cells.push(OracleClippedVoronoiCell {
+            generator_index,
+            clipped: cell.iter().any(|&(_, source)| source == EdgeSource::Domain),
+            polygon: cell.iter().map(|&(p, _)| point(p)).collect(),
+        });
+    }
+
+    Ok(OracleClippedVoronoiOutput {
+        triangulation: triangle_output(triangulation, index, points),
+        domain: clip.into_iter().map(point).collect(),
+        cells,
+        degenerate_cells,
+        outside_domain,
+    })
+}
+
+/// One Sutherland-Hodgman step: keeps the part of `polygon` on the `side` of the line through
+/// `a` and `b` (or on it), tagging the new edge along the line as a Voronoi edge.
+pub fn clip_to_side(polygon: &[Corner], a: Point2<f64>, b: Point2<f64>, side: f64) -> Vec<Corner> {
+    let mut result: Vec<Corner> = Vec::with_capacity(polygon.len() + 1);
+
+    for (i, &(s, source)) in polygon.iter().enumerate() {
+        let e = polygon[(i + 1) % polygon.len()].0;
+        let fs = orient(a, b, s) * side;
+        let fe = orient(a, b, e) * side;
+        if fs >= 0.0 {
+            push_corner(&mut result, (s, source));
+        }
+        if (fs > 0.0 && fe < 0.0) || (fs < 0.0 && fe > 0.0) {
+            let t = fs / (fs - fe);
+            let crossing = Point2::new(s.x + (e.x - s.x) * t, s.y + (e.y - s.y) * t);
+            // Leaving the kept side turns onto the line; entering it continues the old edge.
+            let next = if fs > 0.0 {
+                EdgeSource::Voronoi
+            } else {
+                source
+            };
+            push_corner(&mut result, (crossing, next));
+        } else if fs >= 0.0 && fe < 0.0 {
+            // `s` is on the line, so the kept boundary turns onto the line right there.
+            if let Some(last) = result.last_mut() {
+                last.1 = EdgeSource::Voronoi;
+            }
+        }
+    }
+
+    while result.len() > 1 && result[0].0 == result[result.len() - 1].0 {
+        result.pop();
+    }
+    result
+}
+
+/// Appends `corner` unless it repeats the last point.
+fn push_corner(polygon: &mut Vec<Corner>, corner: Corner) {
+    if polygon.last().map(|&(p, _)| p) != Some(corner.0) {
+        polygon.push(corner);
+    }
+}
+
+fn contains(polygon: &[Point2<f64>], p: Point2<f64>) -> bool {
+    (0..polygon.len()).all(|i| orient(polygon[i], polygon[(i + 1) % polygon.len()], p) >= 0.0)
+}
+
+fn is_convex(polygon: &[Point2<f64>]) -> bool {
+    let n = polygon.len();
+    (0..n).all(|i| orient(polygon[i], polygon[(i + 1) % n], polygon[(i + 2) % n]) >= 0.0)
+}
+
+pub fn signed_area(polygon: &[Point2<f64>]) -> f64 {
+    let n = polygon.len();
+    (0..n)
+        .map(|i| {
+            let (p, q) = (polygon[i], polygon[(i + 1) % n]);
+            p.x * q.y - q.x * p.y
+        })
+        .sum::<f64>()
+        / 2.0
+}
+
+/// Positive when `c` is left of the line from `a` to `b`, exactly.
+pub fn orient(a: Point2<f64>, b: Point2<f64>, c: Point2<f64>) -> f64 {
+    let coord = |p: Point2<f64>| robust::Coord { x: p.x, y: p.y };
+    robust::orient2d(coord(a), coord(b), coord(c))
+}
+
+fn point(p: Point2<f64>) -> OraclePoint {
+    OraclePoint { x: p.x, y: p.y }
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+
+    use super::*;
+    use crate::coverage::{verify_coverage, OracleCoverageInput};
+    use crate::model::{OracleDomainPolygon, OracleInput};
+    use crate::{build_delaunay, Cli, Command};
+
+    fn square(min: f64, max: f64) -> OracleDomain {
+        let corner = |x, y| OraclePoint { x, y };
+        OracleDomain {
+            kind: "polygon".to_string(),
+            polygon: Some(OracleDomainPolygon {
+                vertices: vec![
+                    corner(min, min),
+                    corner(max, min),
+                    corner(max, max),
+                    corner(min, max),
+                ],
+            }),
+            holes: Vec::new(),
+        }
+    }
+
+    /// Runs `generate <args>`, then `clipped-voronoi` on `domain`, then `verify-coverage` on its
+    /// JSON output.
+    fn assert_covers(args: &str, domain: OracleDomain) {
+        let command = ["oracle", "generate"].into_iter().chain(args.split(' '));
+        let Command::Generate { generator, .. } = Cli::parse_from(command).command else {
+            unreachable!();
+        };
+        let input = OracleInput {
+            domain: Some(domain),
+            ..generator.generate().unwrap()
+        };
+        let (triangulation, index) = build_delaunay(&input);
+        let output =
+            clipped_voronoi(&triangulation, &index, &input.points, input.domain.as_ref()).unwrap();
+        let cells: OracleCoverageInput =
+            serde_json::from_value(serde_json::to_value(&output).unwrap()).unwrap();
+        let report = verify_coverage(&cells, None, 1e-9).unwrap();
+        assert!(
+            report.valid,
+            "{args}: {:?}, covered {} of {}",
+            report.violations, report.covered_area, report.domain_area
+        );
+        assert_eq!(report.cell_count, input.points.len(), "{args}");
+    }
+
+    #[test]
+    fn cocircular_cells_cover_the_domain() {
+        for count in [3, 4, 7, 12, 13, 24, 37, 50, 100] {
+            assert_covers(&format!("cocircular --count {count}"), square(-2.0, 2.0));
+        }
+    }
+
+    #[test]
+    fn lattice_cells_cover_the_domain() {
+        assert_covers("grid --width 5 --height 4", square(-1.0, 5.0));
+        assert_covers("triangular --width 5 --height 4", square(-1.0, 5.0));
+        assert_covers("hexagonal --width 5 --height 4", square(-1.0, 5.0));
+        assert_covers("grid --width 5 --height 4 --rotation 30", square(-4.0, 6.0));
+    }
+
+    #[test]
+    fn collinear_cells_cover_the_domain() {
+        assert_covers("collinear --count 5", square(-1.0, 5.0));
+        assert_covers("collinear --count 5 --dx 0 --dy 1", square(-1.0, 5.0));
+        assert_covers("collinear --count 5 --dy 1", square(-1.0, 5.0));
+    }
+}
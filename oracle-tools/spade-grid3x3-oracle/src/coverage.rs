@@ -0,0 +1,321 @@
+//! Checks that a set of clipped Voronoi cells tiles its domain: no gaps, no overlaps.
+//!
+//! Reads any JSON with `cells` of `generatorIndex` and `polygon`, such as the `clipped-voronoi`
+//! output or a serialized .NET `ClippedVoronoiDiagram`. The domain and generator positions come
+//! from an `OracleInput` when one is given, otherwise from the same file's `domain` and `points`.
+//!
+//! Tolerances scale with the domain: areas may be off by `epsilon` times the domain area and
+//! distances by `epsilon` times its square root. Orientation signs use the exact `orient2d`.
+
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use spade::Point2;
+
+use crate::clipped_voronoi::{clip_to_side, orient, signed_area, EdgeSource};
+use crate::model::{OracleInput, OraclePoint};
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OracleCoverageInput {
+    #[serde(default)]
+    pub points: Vec<OraclePoint>,
+    #[serde(default)]
+    pub domain: Vec<OraclePoint>,
+    pub cells: Vec<OracleCoverageCell>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OracleCoverageCell {
+    pub generator_index: usize,
+    pub polygon: Vec<OraclePoint>,
+}
+
+impl OracleCoverageInput {
+    pub fn read_from_file(path: &Path) -> Result<Self, Box<dyn Error>> {
+        let json = fs::read_to_string(path)?;
+        serde_json::from_str(&json).map_err(|e| format!("{}: {e}", path.display()).into())
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OracleCoverageReport {
+    pub epsilon: f64,
+    pub domain_area: f64,
+    /// Sum of the cell areas, counting clockwise cells as negative.
+    pub covered_area: f64,
+    pub area_matches: bool,
+    pub cell_count: usize,
+    /// Sorted by generator index.
+    pub violations: Vec<OracleCoverageViolation>,
+    /// `areaMatches` and no violations.
+    pub valid: bool,
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OracleCoverageViolation {
+    pub generator_index: usize,
+    #[serde(flatten)]
+    pub kind: OracleViolationKind,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(
+    tag = "kind",
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase"
+)]
+pub enum OracleViolationKind {
+    /// More than one cell has this generator index.
+    DuplicateCell,
+    /// The generator index is not a position in `points`.
+    MissingGenerator,
+    TooFewVertices,
+    /// The signed area is not positive.
+    NotCounterclockwise {
+        area: f64,
+    },
+    /// The polygon turns right at `vertex` by more than the tolerance.
+    NotConvex {
+        vertex: usize,
+    },
+    /// The generator lies `distance` outside its cell.
+    GeneratorOutside {
+        distance: f64,
+    },
+    /// `area` of the cell lies outside the domain.
+    OutsideDomain {
+        area: f64,
+    },
+    /// The interiors of this cell and the cell of `other` share `area`. Reported for both.
+    Overlap {
+        other: usize,
+        area: f64,
+    },
+}
+
+pub fn verify_coverage(
+    cells: &OracleCoverageInput,
+    input: Option<&OracleInput>,
+    epsilon: f64,
+) -> Result<OracleCoverageReport, Box<dyn Error>> {
+    if !(epsilon.is_finite() && epsilon >= 0.0) {
+        return Err(
+            format!("coverage needs a non-negative, finite epsilon, found {epsilon}").into(),
+        );
+    }
+    let (points, domain) = match input {
+        Some(input) => {
+            let polygon = input
+                .domain
+                .as_ref()
+                .and_then(|d| d.polygon.as_ref())
+                .ok_or("the input has no domain polygon")?;
+            (&input.points, &polygon.vertices)
+        }
+        None => (&cells.points, &cells.domain),
+    };
+    if domain.len() < 3 {
+        return Err("coverage needs a domain polygon with at least 3 vertices".into());
+    }
+    let mut domain: Vec<Point2<f64>> = domain.iter().map(position).collect();
+    if signed_area(&domain) < 0.0 {
+        domain.reverse();
+    }
+    let domain_area = signed_area(&domain);
+    let area_tolerance = epsilon * domain_area;
+    let length_tolerance = epsilon * domain_area.sqrt();
+    if convexity_violation(&domain, length_tolerance).is_some() {
+        return Err("coverage needs a convex domain polygon".into());
+    }
+
+    let mut violations = Vec::new();
+    let mut report = |generator_index, kind| {
+        violations.push(OracleCoverageViolation {
+            generator_index,
+            kind,
+        })
+    };
+
+    let mut by_generator: BTreeMap<usize, Vec<Point2<f64>>> = BTreeMap::new();
+    let mut covered_area = 0.0;
+    for cell in &cells.cells {
+        let polygon: Vec<Point2<f64>> = cell.polygon.iter().map(position).collect();
+        covered_area += signed_area(&polygon);
+        if by_generator.insert(cell.generator_index, polygon).is_some() {
+            report(cell.generator_index, OracleViolationKind::DuplicateCell);
+        }
+    }
+
+    // Cells that are convex and counterclockwise, for the pairwise overlap check.
+    let mut valid_cells = Vec::new();
+    for (&generator_index, polygon) in &by_generator {
+        if polygon.len() < 3 {
+            report(generator_index, OracleViolationKind::TooFewVertices);
+            continue;
+        }
+        let area = signed_area(polygon);
+        let mut well_formed = true;
+        if area <= 0.0 {
+            report(
+                generator_index,
+                OracleViolationKind::NotCounterclockwise { area },
+            );
+            well_formed = false;
+        } else if let Some(vertex) = convexity_violation(polygon, length_tolerance) {
+            report(generator_index, OracleViolationKind::NotConvex { vertex });
+            well_formed = false;
+        }
+
+        match points.get(generator_index) {
+            // Only meaningful for a counterclockwise polygon.
+            Some(_) if area <= 0.0 => {}
+            Some(site) => {
+                let distance = distance_outside(polygon, position(site));
+                if distance > length_tolerance {
+                    report(
+                        generator_index,
+                        OracleViolationKind::GeneratorOutside { distance },
+                    );
+                }
+            }
+            None => report(generator_index, OracleViolationKind::MissingGenerator),
+        }
+
+        if well_formed {
+            let outside = area - intersection_area(polygon, &domain);
+            if outside > area_tolerance {
+                report(
+                    generator_index,
+                    OracleViolationKind::OutsideDomain { area: outside },
+                );
+            }
+            valid_cells.push((generator_index, polygon, bounds(polygon)));
+        }
+    }
+
+    for (i, &(a, polygon_a, bounds_a)) in valid_cells.iter().enumerate() {
+        for &(b, polygon_b, bounds_b) in &valid_cells[i + 1..] {
+            if !overlaps(bounds_a, bounds_b) {
+                continue;
+            }
+            let area = intersection_area(polygon_a, polygon_b);
+            if area > area_tolerance {
+                report(a, OracleViolationKind::Overlap { other: b, area });
+                report(b, OracleViolationKind::Overlap { other: a, area });
+            }
+        }
+    }
+
+    violations.sort_by_key(|v| v.generator_index);
+    let area_matches = (covered_area - domain_area).abs() <= area_tolerance;
+    Ok(OracleCoverageReport {
+        epsilon,
+        domain_area,
+        covered_area,
+        area_matches,
+        cell_count: cells.cells.len(),
+        valid: area_matches && violations.is_empty(),
+        violations,
+    })
+}
+
+/// The first vertex where the counterclockwise `polygon` turns right by more than `tolerance`,
+/// measured as the distance of the next vertex from the previous edge's line.
+fn convexity_violation(polygon: &[Point2<f64>], tolerance: f64) -> Option<usize> {
+    let n = polygon.len();
+    (0..n).find(|&i| {
+        let (a, b, c) = (polygon[(i + n - 1) % n], polygon[i], polygon[(i + 1) % n]);
+        let length = distance(a, b);
+        length > 0.0 && orient(a, b, c) / length < -tolerance
+    })
+}
+
+/// How far `p` lies outside the counterclockwise convex `polygon`; zero or less when inside.
+fn distance_outside(polygon: &[Point2<f64>], p: Point2<f64>) -> f64 {
+    let n = polygon.len();
+    (0..n)
+        .filter_map(|i| {
+            let (a, b) = (polygon[i], polygon[(i + 1) % n]);
+            let length = distance(a, b);
+            (length > 0.0).then(|| -orient(a, b, p) / length)
+        })
+        .fold(f64::NEG_INFINITY, f64::max)
+}
+
+/// Area of the intersection of two counterclockwise convex polygons.
+fn intersection_area(subject: &[Point2<f64>], clip: &[Point2<f64>]) -> f64 {
+    let mut polygon: Vec<_> = subject.iter().map(|&p| (p, EdgeSource::Domain)).collect();
+    for i in 0..clip.len() {
+        let (a, b) = (clip[i], clip[(i + 1) % clip.len()]);
+        if a == b {
+            continue;
+        }
+        polygon = clip_to_side(&polygon, a, b, 1.0);
+        if polygon.len() < 3 {
+            return 0.0;
+        }
+    }
+    let points: Vec<_> = polygon.into_iter().map(|(p, _)| p).collect();
+    signed_area(&points)
+}
+
+type Bounds = [f64; 4];
+
+fn bounds(polygon: &[Point2<f64>]) -> Bounds {
+    polygon.iter().fold(
+        [
+            f64::INFINITY,
+            f64::INFINITY,
+            f64::NEG_INFINITY,
+            f64::NEG_INFINITY,
+        ],
+        |[min_x, min_y, max_x, max_y], p| {
+            [
+                min_x.min(p.x),
+                min_y.min(p.y),
+                max_x.max(p.x),
+                max_y.max(p.y),
+            ]
+        },
+    )
+}
+
+fn overlaps(a: Bounds, b: Bounds) -> bool {
+    a[0] <= b[2] && b[0] <= a[2] && a[1] <= b[3] && b[1] <= a[3]
+}
+
+fn distance(a: Point2<f64>, b: Point2<f64>) -> f64 {
+    let (dx, dy) = (b.x - a.x, b.y - a.y);
+    (dx * dx + dy * dy).sqrt()
+}
+
+fn position(p: &OraclePoint) -> Point2<f64> {
+    Point2::new(p.x, p.y)
+}
+
+pub fn render_coverage_text(report: &OracleCoverageReport) -> String {
+    let mut text = format!(
+        "{} cells covering {:e} of a domain of {:e} ({})\n",
+        report.cell_count,
+        report.covered_area,
+        report.domain_area,
+        if report.area_matches {
+            "matches"
+        } else {
+            "mismatch"
+        }
+    );
+    for v in &report.violations {
+        let _ = writeln!(text, "generator {}: {:?}", v.generator_index, v.kind);
+    }
+    text.push_str(if report.valid { "valid\n" } else { "invalid\n" });
+    text
+}
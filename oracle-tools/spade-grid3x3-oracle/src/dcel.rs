@@ -0,0 +1,94 @@
+//! Handle-by-handle dump of spade's DCEL.
+//!
+//! Indices are spade's fixed handle indices, which use the same layout as the .NET `Dcel`:
+//! directed edge `2 * u + d` belongs to undirected edge `u`, its twin is `index ^ 1`, and face 0
+//! is the outer face. Vertices additionally carry the input index that created them.
+
+use serde::Serialize;
+use spade::handles::OUTER_FACE;
+use spade::{Point2, Triangulation};
+
+use crate::model::OraclePoint;
+use crate::vertex_index::VertexIndex;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OracleDcelOutput {
+    pub points: Vec<OraclePoint>,
+    pub outer_face: usize,
+    pub vertices: Vec<OracleDcelVertex>,
+    pub edges: Vec<OracleDcelEdge>,
+    pub faces: Vec<OracleDcelFace>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OracleDcelVertex {
+    pub handle: usize,
+    pub input_index: usize,
+    pub out_edge: Option<usize>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OracleDcelEdge {
+    pub handle: usize,
+    pub origin: usize,
+    pub twin: usize,
+    pub next: usize,
+    pub prev: usize,
+    pub face: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OracleDcelFace {
+    pub handle: usize,
+    pub adjacent_edge: Option<usize>,
+}
+
+pub fn dump_dcel<T>(
+    triangulation: &T,
+    index: &VertexIndex,
+    points: &[OraclePoint],
+) -> OracleDcelOutput
+where
+    T: Triangulation<Vertex = Point2<f64>>,
+{
+    let vertices = triangulation
+        .vertices()
+        .map(|v| OracleDcelVertex {
+            handle: v.fix().index(),
+            input_index: index.input_index(v.fix()),
+            out_edge: v.out_edge().map(|e| e.fix().index()),
+        })
+        .collect();
+
+    let edges = triangulation
+        .directed_edges()
+        .map(|e| OracleDcelEdge {
+            handle: e.fix().index(),
+            origin: e.from().fix().index(),
+            twin: e.rev().fix().index(),
+            next: e.next().fix().index(),
+            prev: e.prev().fix().index(),
+            face: e.face().fix().index(),
+        })
+        .collect();
+
+    let faces = triangulation
+        .all_faces()
+        .map(|f| OracleDcelFace {
+            handle: f.fix().index(),
+            adjacent_edge: f.adjacent_edge().map(|e| e.fix().index()),
+        })
+        .collect();
+
+    OracleDcelOutput {
+        points: points.to_vec(),
+        outer_face: OUTER_FACE.index(),
+        vertices,
+        edges,
+        faces,
+    }
+}
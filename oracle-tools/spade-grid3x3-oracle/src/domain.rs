@@ -0,0 +1,174 @@
+//! Polygon domains with holes, inserted into the CDT as constraint loops.
+//!
+//! Ring vertices are appended after the input points and ring edges after the input
+//! constraints, outer ring first, then the holes in input order. Faces are classified with
+//! spade's own flood fill: `refine` with `exclude_outer_faces` and no additional vertices returns
+//! the faces outside of the domain without changing the triangulation. The flood fill only
+//! tells inside from outside, so the layer depth is replayed from the same peeling walk to tell
+//! faces beyond the outer ring from faces inside a hole.
+
+use std::collections::HashSet;
+
+use serde::Serialize;
+use spade::handles::DirectedEdgeHandle;
+use spade::{RefinementParameters, Triangulation};
+
+use crate::cdt::Cdt;
+use crate::model::{OracleDomain, OraclePoint};
+use crate::vertex_index::VertexIndex;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OracleDomainOutput {
+    pub rings: Vec<OracleDomainRing>,
+    /// Every inner face, sorted by triangle.
+    pub faces: Vec<OracleDomainFace>,
+    /// Whether the replayed peeling walk excluded exactly the faces spade excluded.
+    pub flood_fill_matches_spade: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OracleDomainRing {
+    pub kind: OracleRingKind,
+    /// Indices into the output `points`, in ring order.
+    pub vertices: Vec<usize>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum OracleRingKind {
+    Outer,
+    Hole,
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OracleDomainFace {
+    pub triangle: [usize; 3],
+    pub region: OracleFaceRegion,
+    /// Number of constraint layers crossed from the convex hull.
+    pub depth: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum OracleFaceRegion {
+    /// Not excluded by spade's flood fill.
+    Inside,
+    /// Excluded, and reached from the convex hull without crossing a constraint.
+    Outside,
+    /// Excluded, behind at least one constraint layer.
+    Hole,
+}
+
+/// Returns the ring vertices, to be appended to the input points, and the rings with their
+/// vertices numbered from `first_index`.
+pub fn domain_rings(
+    domain: Option<&OracleDomain>,
+    first_index: usize,
+) -> (Vec<OraclePoint>, Vec<OracleDomainRing>) {
+    let Some(outer) = domain.and_then(|d| d.polygon.as_ref()) else {
+        return (Vec::new(), Vec::new());
+    };
+    let holes = domain.map_or(&[][..], |d| &d.holes[..]);
+
+    let mut points = Vec::new();
+    let mut rings = Vec::new();
+    let polygons = std::iter::once((OracleRingKind::Outer, outer))
+        .chain(holes.iter().map(|hole| (OracleRingKind::Hole, hole)));
+    for (kind, polygon) in polygons {
+        let start = first_index + points.len();
+        points.extend_from_slice(&polygon.vertices);
+        rings.push(OracleDomainRing {
+            kind,
+            vertices: (start..start + polygon.vertices.len()).collect(),
+        });
+    }
+    (points, rings)
+}
+
+/// The closed loop of edges around `ring`.
+pub fn ring_edges(ring: &OracleDomainRing) -> impl Iterator<Item = [usize; 2]> + '_ {
+    let v = &ring.vertices;
+    (0..v.len()).map(move |i| [v[i], v[(i + 1) % v.len()]])
+}
+
+pub fn classify_faces(cdt: &Cdt, index: &VertexIndex) -> (Vec<OracleDomainFace>, bool) {
+    let parameters = RefinementParameters::<f64>::new()
+        .exclude_outer_faces(true)
+        .with_max_additional_vertices(0);
+    let excluded: HashSet<usize> = cdt
+        .clone()
+        .refine(parameters)
+        .excluded_faces
+        .into_iter()
+        .map(|f| f.index())
+        .collect();
+
+    let (replayed, depths) = peel_layers(cdt);
+
+    let mut faces: Vec<OracleDomainFace> = cdt
+        .inner_faces()
+        .map(|face| {
+            let mut triangle = face.vertices().map(|v| index.input_index(v.fix()));
+            triangle.sort();
+            let depth = depths[face.fix().index()];
+            let region = match (excluded.contains(&face.fix().index()), depth) {
+                (false, _) => OracleFaceRegion::Inside,
+                (true, 0) => OracleFaceRegion::Outside,
+                (true, _) => OracleFaceRegion::Hole,
+            };
+            OracleDomainFace {
+                triangle,
+                region,
+                depth,
+            }
+        })
+        .collect();
+    faces.sort_by_key(|f| f.triangle);
+
+    (faces, replayed == excluded)
+}
+
+/// Replays spade's `calculate_outer_faces`: peel faces layer by layer from the convex hull,
+/// stepping one layer deeper at every constraint edge. Returns the faces on even layers (the
+/// ones spade excludes) and the first layer each face was reached on, indexed by face handle.
+fn peel_layers(cdt: &Cdt) -> (HashSet<usize>, Vec<usize>) {
+    let mut depths = vec![usize::MAX; cdt.num_all_faces()];
+    let mut layers = [HashSet::new(), HashSet::new()];
+    if cdt.all_vertices_on_line() {
+        return (HashSet::new(), depths);
+    }
+
+    let mut current: Vec<DirectedEdgeHandle<_, _, _, _>> =
+        cdt.convex_hull().map(|edge| edge.rev()).collect();
+    let mut next = Vec::new();
+    let mut layer = 0;
+
+    loop {
+        while let Some(edge) = current.pop() {
+            let crosses = edge.is_constraint_edge();
+            let face_layer = layer + usize::from(crosses);
+            let Some(face) = edge.face().as_inner() else {
+                continue;
+            };
+            if layers[face_layer % 2].insert(face.fix().index()) {
+                let depth = &mut depths[face.fix().index()];
+                *depth = (*depth).min(face_layer);
+                let list = if crosses { &mut next } else { &mut current };
+                list.push(edge.prev().rev());
+                list.push(edge.next().rev());
+            }
+        }
+
+        if next.is_empty() {
+            break;
+        }
+        std::mem::swap(&mut current, &mut next);
+        layer += 1;
+    }
+
+    let [even, _] = layers;
+    (even, depths)
+}
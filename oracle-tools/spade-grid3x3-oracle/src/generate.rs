@@ -0,0 +1,91 @@
+//! Built-in generators that produce `OracleInput` point sets.
+//!
+//! Every generated input records the generator and its parameters under `generator`, so a file
+//! can be regenerated from its own contents.
+
+mod degenerate;
+mod lattice;
+mod random;
+
+use std::error::Error;
+
+use clap::Subcommand;
+use serde::Serialize;
+
+use crate::model::OracleInput;
+
+pub use degenerate::{CocircularArgs, CollinearArgs, MixtureArgs, NearDuplicateArgs};
+pub use lattice::LatticeArgs;
+pub use random::{AnnulusArgs, ClusterArgs, PoissonDiskArgs, UniformArgs};
+
+#[derive(Debug, Clone, Subcommand, Serialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum Generator {
+    /// Rectangular grid of `width` x `height` points.
+    Grid(LatticeArgs),
+    /// Triangular lattice: rows offset by half a spacing, every triangle equilateral.
+    Triangular(LatticeArgs),
+    /// Hexagonal (honeycomb) lattice: the triangular lattice without its hexagon centers.
+    Hexagonal(LatticeArgs),
+    /// `count` points drawn uniformly from a rectangle.
+    Uniform(UniformArgs),
+    /// `count` points around Gaussian cluster centers.
+    Clusters(ClusterArgs),
+    /// Blue-noise points at least `radius` apart, up to `count` of them.
+    PoissonDisk(PoissonDiskArgs),
+    /// `count` points drawn uniformly from an annulus.
+    Annulus(AnnulusArgs),
+    /// `count` points on a line, `dx`/`dy` apart.
+    Collinear(CollinearArgs),
+    /// `count` points evenly spaced on a circle.
+    Cocircular(CocircularArgs),
+    /// `count` uniform points, each followed by `copies` copies a few ULPs away.
+    NearDuplicates(NearDuplicateArgs),
+    /// Uniform points mixed with a collinear row, a circle and 1-ULP near-duplicates.
+    Mixture(MixtureArgs),
+}
+
+impl Generator {
+    pub fn generate(&self) -> Result<OracleInput, Box<dyn Error>> {
+        if let Some(bounds) = self.bounds() {
+            bounds.validate()?;
+        }
+        let points = match self {
+            Generator::Grid(args) => lattice::grid(args),
+            Generator::Triangular(args) => lattice::triangular(args),
+            Generator::Hexagonal(args) => lattice::hexagonal(args),
+            Generator::Uniform(args) => random::uniform(args),
+            Generator::Clusters(args) => random::clusters(args),
+            Generator::PoissonDisk(args) => random::poisson_disk(args)?,
+            Generator::Annulus(args) => random::annulus(args),
+            Generator::Collinear(args) => degenerate::collinear(args),
+            Generator::Cocircular(args) => degenerate::cocircular(args),
+            Generator::NearDuplicates(args) => degenerate::near_duplicates(args),
+            Generator::Mixture(args) => degenerate::mixture(args),
+        };
+
+        Ok(OracleInput {
+            points,
+            weights: None,
+            domain: None,
+            constraints: Vec::new(),
+            refinement: None,
+            values: Vec::new(),
+            queries: Vec::new(),
+            grid: None,
+            generator: Some(serde_json::to_value(self)?),
+        })
+    }
+
+    /// The rectangle the generator samples from, if it has one.
+    fn bounds(&self) -> Option<&random::BoundsArgs> {
+        match self {
+            Generator::Uniform(args) => Some(&args.bounds),
+            Generator::Clusters(args) => Some(&args.bounds),
+            Generator::PoissonDisk(args) => Some(&args.bounds),
+            Generator::NearDuplicates(args) => Some(&args.bounds),
+            Generator::Mixture(args) => Some(&args.bounds),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,207 @@
+//! Degenerate point sets for the RFC-009 degeneracy tests.
+//!
+//! Collinear points are exactly collinear as long as the step is exactly representable times
+//! every index (integers or powers of two). Cocircular points are only exact where `sin`/`cos`
+//! are; the rest lie within rounding of the circle, which is the case spade's robust predicates
+//! have to resolve.
+
+use std::f64::consts::TAU;
+
+use clap::Args;
+use serde::Serialize;
+
+use super::random::{BoundsArgs, RandomArgs};
+use crate::model::OraclePoint;
+use crate::rng::SplitMix64;
+
+#[derive(Debug, Clone, Args, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CollinearArgs {
+    #[command(flatten)]
+    #[serde(flatten)]
+    pub random: RandomArgs,
+    /// Offset between consecutive points along the line.
+    #[arg(long, default_value_t = 1.0)]
+    pub dx: f64,
+    #[arg(long, default_value_t = 0.0)]
+    pub dy: f64,
+    /// Emit the points in seeded random order instead of along the line.
+    #[arg(long)]
+    pub shuffle: bool,
+}
+
+#[derive(Debug, Clone, Args, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CocircularArgs {
+    #[command(flatten)]
+    #[serde(flatten)]
+    pub random: RandomArgs,
+    #[arg(long, default_value_t = 0.0)]
+    pub center_x: f64,
+    #[arg(long, default_value_t = 0.0)]
+    pub center_y: f64,
+    #[arg(long, default_value_t = 1.0)]
+    pub radius: f64,
+    /// Emit the points in seeded random order instead of counterclockwise.
+    #[arg(long)]
+    pub shuffle: bool,
+}
+
+#[derive(Debug, Clone, Args, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NearDuplicateArgs {
+    /// Number of base points; each is followed by its copies.
+    #[command(flatten)]
+    #[serde(flatten)]
+    pub random: RandomArgs,
+    #[command(flatten)]
+    #[serde(flatten)]
+    pub bounds: BoundsArgs,
+    /// Copies emitted after each base point.
+    #[arg(long, default_value_t = 1)]
+    pub copies: usize,
+    /// Each copy moves every coordinate by a uniform number of ULPs in `[-ulps, ulps]`. A copy
+    /// that moves by zero in both coordinates is an exact duplicate.
+    #[arg(long, default_value_t = 1)]
+    pub ulps: u32,
+}
+
+#[derive(Debug, Clone, Args, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MixtureArgs {
+    /// Number of uniform background points.
+    #[command(flatten)]
+    #[serde(flatten)]
+    pub random: RandomArgs,
+    #[command(flatten)]
+    #[serde(flatten)]
+    pub bounds: BoundsArgs,
+    /// Points on the horizontal center line of the bounds.
+    #[arg(long, default_value_t = 8)]
+    pub line: usize,
+    /// Points on a circle around the center of the bounds.
+    #[arg(long, default_value_t = 8)]
+    pub circle: usize,
+    /// Copies of randomly chosen earlier points, one ULP up or down in each coordinate. A copy
+    /// never lands on its source, but may land on another copy or, when the source is itself a
+    /// copy, on an earlier point.
+    #[arg(long, default_value_t = 8)]
+    pub duplicates: usize,
+    /// Emit all points in seeded random order instead of component by component.
+    #[arg(long)]
+    pub shuffle: bool,
+}
+
+/// Moves `value` by `ulps` representable steps; negative counts move down.
+fn step_ulps(value: f64, ulps: i64) -> f64 {
+    let step = if ulps < 0 {
+        f64::next_down
+    } else {
+        f64::next_up
+    };
+    (0..ulps.unsigned_abs()).fold(value, |v, _| step(v))
+}
+
+fn ulp_offset(rng: &mut SplitMix64, ulps: u32) -> i64 {
+    rng.index(2 * ulps as usize + 1) as i64 - ulps as i64
+}
+
+/// Like [`ulp_offset`], but never zero.
+fn nonzero_ulp_offset(rng: &mut SplitMix64, ulps: u32) -> i64 {
+    let offset = rng.index(2 * ulps as usize) as i64 - ulps as i64;
+    if offset < 0 {
+        offset
+    } else {
+        offset + 1
+    }
+}
+
+fn ring(count: usize, center: OraclePoint, radius: f64) -> impl Iterator<Item = OraclePoint> {
+    (0..count).map(move |i| {
+        let (sin, cos) = (TAU * i as f64 / count as f64).sin_cos();
+        OraclePoint {
+            x: center.x + radius * cos,
+            y: center.y + radius * sin,
+        }
+    })
+}
+
+pub fn collinear(args: &CollinearArgs) -> Vec<OraclePoint> {
+    let mut points: Vec<_> = (0..args.random.count)
+        .map(|i| OraclePoint {
+            x: i as f64 * args.dx,
+            y: i as f64 * args.dy,
+        })
+        .collect();
+    if args.shuffle {
+        SplitMix64::new(args.random.seed).shuffle(&mut points);
+    }
+    points
+}
+
+pub fn cocircular(args: &CocircularArgs) -> Vec<OraclePoint> {
+    let center = OraclePoint {
+        x: args.center_x,
+        y: args.center_y,
+    };
+    let mut points: Vec<_> = ring(args.random.count, center, args.radius).collect();
+    if args.shuffle {
+        SplitMix64::new(args.random.seed).shuffle(&mut points);
+    }
+    points
+}
+
+pub fn near_duplicates(args: &NearDuplicateArgs) -> Vec<OraclePoint> {
+    let mut rng = SplitMix64::new(args.random.seed);
+    let mut points = Vec::with_capacity(args.random.count * (args.copies + 1));
+
+    for _ in 0..args.random.count {
+        let base = args.bounds.sample(&mut rng);
+        points.push(base);
+        for _ in 0..args.copies {
+            let x = step_ulps(base.x, ulp_offset(&mut rng, args.ulps));
+            let y = step_ulps(base.y, ulp_offset(&mut rng, args.ulps));
+            points.push(OraclePoint { x, y });
+        }
+    }
+    points
+}
+
+/// Uniform background, then the center line, then the circle, then near-duplicates of earlier
+/// points from any of those, including earlier near-duplicates; see [`MixtureArgs::duplicates`]
+/// for when they are exact.
+pub fn mixture(args: &MixtureArgs) -> Vec<OraclePoint> {
+    let mut rng = SplitMix64::new(args.random.seed);
+    let bounds = &args.bounds;
+    let center = OraclePoint {
+        x: (bounds.min_x + bounds.max_x) / 2.0,
+        y: (bounds.min_y + bounds.max_y) / 2.0,
+    };
+
+    let mut points: Vec<_> = (0..args.random.count)
+        .map(|_| bounds.sample(&mut rng))
+        .collect();
+
+    let width = bounds.max_x - bounds.min_x;
+    points.extend((0..args.line).map(|i| OraclePoint {
+        x: bounds.min_x + width * (i as f64 + 0.5) / args.line as f64,
+        y: center.y,
+    }));
+
+    let radius = 0.25 * width.min(bounds.max_y - bounds.min_y);
+    points.extend(ring(args.circle, center, radius));
+
+    if !points.is_empty() {
+        for _ in 0..args.duplicates {
+            let source = points[rng.index(points.len())];
+            let x = step_ulps(source.x, nonzero_ulp_offset(&mut rng, 1));
+            let y = step_ulps(source.y, nonzero_ulp_offset(&mut rng, 1));
+            points.push(OraclePoint { x, y });
+        }
+    }
+
+    if args.shuffle {
+        rng.shuffle(&mut points);
+    }
+    points
+}
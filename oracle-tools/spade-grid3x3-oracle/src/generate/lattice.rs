@@ -0,0 +1,99 @@
+//! Regular lattices, optionally scaled, rotated and jittered.
+//!
+//! Points are emitted row by row (`y` outer, `x` inner), so `grid --width 3 --height 3` reproduces
+//! the original 3x3 scenario index for index.
+
+use clap::Args;
+use serde::Serialize;
+
+use crate::model::OraclePoint;
+use crate::rng::SplitMix64;
+
+#[derive(Debug, Clone, Args, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LatticeArgs {
+    /// Number of lattice columns.
+    #[arg(long, default_value_t = 3)]
+    pub width: usize,
+    /// Number of lattice rows.
+    #[arg(long, default_value_t = 3)]
+    pub height: usize,
+    /// Distance between neighboring lattice points.
+    #[arg(long, default_value_t = 1.0)]
+    pub spacing: f64,
+    /// Scale factor applied to x before rotating.
+    #[arg(long, default_value_t = 1.0)]
+    pub scale_x: f64,
+    /// Scale factor applied to y before rotating.
+    #[arg(long, default_value_t = 1.0)]
+    pub scale_y: f64,
+    /// Counterclockwise rotation about the origin, in degrees.
+    #[arg(long, default_value_t = 0.0)]
+    pub rotation: f64,
+    /// Each coordinate is offset by a uniform value in `[-jitter, jitter)` after rotating.
+    #[arg(long, default_value_t = 0.0)]
+    pub jitter: f64,
+    #[arg(long, default_value_t = 0)]
+    pub seed: u64,
+}
+
+impl LatticeArgs {
+    fn place(&self, lattice: impl IntoIterator<Item = (f64, f64)>) -> Vec<OraclePoint> {
+        let (sin, cos) = self.rotation.to_radians().sin_cos();
+        let mut rng = SplitMix64::new(self.seed);
+
+        lattice
+            .into_iter()
+            .map(|(x, y)| {
+                let x = x * self.spacing * self.scale_x;
+                let y = y * self.spacing * self.scale_y;
+                let (mut x, mut y) = if self.rotation == 0.0 {
+                    (x, y)
+                } else {
+                    (x * cos - y * sin, x * sin + y * cos)
+                };
+                if self.jitter != 0.0 {
+                    x += rng.range(-self.jitter, self.jitter);
+                    y += rng.range(-self.jitter, self.jitter);
+                }
+                OraclePoint { x, y }
+            })
+            .collect()
+    }
+
+    fn rows(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        (0..self.height).flat_map(move |row| (0..self.width).map(move |column| (column, row)))
+    }
+}
+
+pub fn grid(args: &LatticeArgs) -> Vec<OraclePoint> {
+    args.place(args.rows().map(|(column, row)| (column as f64, row as f64)))
+}
+
+/// Offset rows of a triangular lattice, in units of the spacing.
+fn triangular_position(column: usize, row: usize) -> (f64, f64) {
+    let offset = if row % 2 == 1 { 0.5 } else { 0.0 };
+    (column as f64 + offset, row as f64 * (3.0f64.sqrt() / 2.0))
+}
+
+pub fn triangular(args: &LatticeArgs) -> Vec<OraclePoint> {
+    args.place(
+        args.rows()
+            .map(|(column, row)| triangular_position(column, row)),
+    )
+}
+
+pub fn hexagonal(args: &LatticeArgs) -> Vec<OraclePoint> {
+    // In axial coordinates `q = column - floor(row / 2)`, `r = row`, the hexagon centers are the
+    // index-3 sublattice `q - r ≡ 0 (mod 3)`.
+    let is_center = |column: usize, row: usize| {
+        let q = column as i64 - (row / 2) as i64;
+        (q - row as i64).rem_euclid(3) == 0
+    };
+
+    args.place(
+        args.rows()
+            .filter(|&(column, row)| !is_center(column, row))
+            .map(|(column, row)| triangular_position(column, row)),
+    )
+}
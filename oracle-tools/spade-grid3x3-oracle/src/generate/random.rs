@@ -0,0 +1,264 @@
+//! Seeded random point sets.
+//!
+//! All draws come from [`SplitMix64`] in a fixed order, so a `(generator, seed)` pair always
+//! produces the same points.
+
+use std::collections::HashMap;
+use std::error::Error;
+use std::f64::consts::TAU;
+
+use clap::Args;
+use serde::Serialize;
+
+use crate::model::OraclePoint;
+use crate::rng::SplitMix64;
+
+#[derive(Debug, Clone, Args, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RandomArgs {
+    /// Number of points to generate.
+    #[arg(long, default_value_t = 100)]
+    pub count: usize,
+    #[arg(long, default_value_t = 0)]
+    pub seed: u64,
+}
+
+/// Axis-aligned rectangle the points are drawn from.
+#[derive(Debug, Clone, Args, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BoundsArgs {
+    #[arg(long, default_value_t = 0.0)]
+    pub min_x: f64,
+    #[arg(long, default_value_t = 0.0)]
+    pub min_y: f64,
+    #[arg(long, default_value_t = 1.0)]
+    pub max_x: f64,
+    #[arg(long, default_value_t = 1.0)]
+    pub max_y: f64,
+}
+
+impl BoundsArgs {
+    pub(super) fn validate(&self) -> Result<(), Box<dyn Error>> {
+        let finite = [self.min_x, self.min_y, self.max_x, self.max_y]
+            .iter()
+            .all(|v| v.is_finite());
+        if !(finite && self.min_x < self.max_x && self.min_y < self.max_y) {
+            return Err(format!(
+                "bounds need finite coordinates with min < max, found x {}..{}, y {}..{}",
+                self.min_x, self.max_x, self.min_y, self.max_y
+            )
+            .into());
+        }
+        Ok(())
+    }
+
+    pub(super) fn sample(&self, rng: &mut SplitMix64) -> OraclePoint {
+        let x = rng.range(self.min_x, self.max_x);
+        let y = rng.range(self.min_y, self.max_y);
+        OraclePoint { x, y }
+    }
+
+    fn contains(&self, p: OraclePoint) -> bool {
+        p.x >= self.min_x && p.x < self.max_x && p.y >= self.min_y && p.y < self.max_y
+    }
+}
+
+#[derive(Debug, Clone, Args, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UniformArgs {
+    #[command(flatten)]
+    #[serde(flatten)]
+    pub random: RandomArgs,
+    #[command(flatten)]
+    #[serde(flatten)]
+    pub bounds: BoundsArgs,
+}
+
+#[derive(Debug, Clone, Args, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClusterArgs {
+    #[command(flatten)]
+    #[serde(flatten)]
+    pub random: RandomArgs,
+    /// Cluster centers are drawn uniformly from these bounds.
+    #[command(flatten)]
+    #[serde(flatten)]
+    pub bounds: BoundsArgs,
+    /// Number of clusters.
+    #[arg(long, default_value_t = 5)]
+    pub clusters: usize,
+    /// Standard deviation of each cluster.
+    #[arg(long, default_value_t = 0.05)]
+    pub sigma: f64,
+}
+
+#[derive(Debug, Clone, Args, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PoissonDiskArgs {
+    /// Upper bound on the number of points; sampling also stops once the bounds are full.
+    #[command(flatten)]
+    #[serde(flatten)]
+    pub random: RandomArgs,
+    #[command(flatten)]
+    #[serde(flatten)]
+    pub bounds: BoundsArgs,
+    /// Minimum distance between any two points.
+    #[arg(long, default_value_t = 0.05)]
+    pub radius: f64,
+    /// Candidates tried around an active sample before it is retired.
+    #[arg(long, default_value_t = 30)]
+    pub attempts: usize,
+}
+
+#[derive(Debug, Clone, Args, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AnnulusArgs {
+    #[command(flatten)]
+    #[serde(flatten)]
+    pub random: RandomArgs,
+    #[arg(long, default_value_t = 0.0)]
+    pub center_x: f64,
+    #[arg(long, default_value_t = 0.0)]
+    pub center_y: f64,
+    #[arg(long, default_value_t = 0.5)]
+    pub inner_radius: f64,
+    #[arg(long, default_value_t = 1.0)]
+    pub outer_radius: f64,
+}
+
+pub fn uniform(args: &UniformArgs) -> Vec<OraclePoint> {
+    let mut rng = SplitMix64::new(args.random.seed);
+    (0..args.random.count)
+        .map(|_| args.bounds.sample(&mut rng))
+        .collect()
+}
+
+/// Standard normal sample via Box-Muller; consumes two uniforms and discards the sine half.
+fn standard_normal(rng: &mut SplitMix64) -> f64 {
+    let u1 = 1.0 - rng.next_f64();
+    let u2 = rng.next_f64();
+    (-2.0 * u1.ln()).sqrt() * (TAU * u2).cos()
+}
+
+pub fn clusters(args: &ClusterArgs) -> Vec<OraclePoint> {
+    let mut rng = SplitMix64::new(args.random.seed);
+    let centers: Vec<_> = (0..args.clusters.max(1))
+        .map(|_| args.bounds.sample(&mut rng))
+        .collect();
+
+    (0..args.random.count)
+        .map(|_| {
+            let center = centers[rng.index(centers.len())];
+            let x = center.x + args.sigma * standard_normal(&mut rng);
+            let y = center.y + args.sigma * standard_normal(&mut rng);
+            OraclePoint { x, y }
+        })
+        .collect()
+}
+
+/// Largest number of background grid cells along either axis.
+const MAX_GRID_CELLS: f64 = (1u64 << 52) as f64;
+
+/// Bridson's algorithm. The first sample is uniform in the bounds; the active sample to expand is
+/// chosen uniformly, and candidates are drawn uniformly from the annulus `[radius, 2 * radius)`.
+pub fn poisson_disk(args: &PoissonDiskArgs) -> Result<Vec<OraclePoint>, Box<dyn Error>> {
+    let radius = args.radius;
+    if !(radius.is_finite() && radius > 0.0) {
+        return Err(format!("poisson-disk needs a positive, finite radius, found {radius}").into());
+    }
+    let mut rng = SplitMix64::new(args.random.seed);
+    let bounds = &args.bounds;
+    let cell = radius / 2.0f64.sqrt();
+    let columns = ((bounds.max_x - bounds.min_x) / cell).ceil().max(1.0);
+    let rows = ((bounds.max_y - bounds.min_y) / cell).ceil().max(1.0);
+    // Cell coordinates must stay exact in `f64` and leave room for the neighborhood scan.
+    if columns.max(rows) > MAX_GRID_CELLS {
+        return Err(format!(
+            "poisson-disk radius {radius:e} is too small for the bounds: {columns:e} x {rows:e} cells"
+        )
+        .into());
+    }
+    let (columns, rows) = (columns as usize, rows as usize);
+    let cell_of = |p: OraclePoint| {
+        let column = (((p.x - bounds.min_x) / cell) as usize).min(columns - 1);
+        let row = (((p.y - bounds.min_y) / cell) as usize).min(rows - 1);
+        (column, row)
+    };
+
+    // Sparse, since a small radius makes far more cells than there are points.
+    let mut grid: HashMap<(usize, usize), usize> = HashMap::new();
+    let mut points = Vec::new();
+    let mut active = Vec::new();
+
+    if args.random.count == 0 {
+        return Ok(points);
+    }
+
+    let first = bounds.sample(&mut rng);
+    let (column, row) = cell_of(first);
+    grid.insert((column, row), 0);
+    points.push(first);
+    active.push(0);
+
+    while !active.is_empty() && points.len() < args.random.count {
+        let slot = rng.index(active.len());
+        let origin = points[active[slot]];
+        let mut placed = false;
+
+        for _ in 0..args.attempts {
+            let angle = TAU * rng.next_f64();
+            let distance = radius * (1.0 + rng.next_f64());
+            let candidate = OraclePoint {
+                x: origin.x + distance * angle.cos(),
+                y: origin.y + distance * angle.sin(),
+            };
+            if !bounds.contains(candidate) {
+                continue;
+            }
+
+            let (column, row) = cell_of(candidate);
+            let too_close = (row.saturating_sub(2)..(row + 3).min(rows)).any(|r| {
+                (column.saturating_sub(2)..(column + 3).min(columns)).any(|c| {
+                    grid.get(&(c, r)).is_some_and(|&i| {
+                        let p = points[i];
+                        let (dx, dy) = (p.x - candidate.x, p.y - candidate.y);
+                        dx * dx + dy * dy < radius * radius
+                    })
+                })
+            });
+            if too_close {
+                continue;
+            }
+
+            grid.insert((column, row), points.len());
+            active.push(points.len());
+            points.push(candidate);
+            placed = true;
+            break;
+        }
+
+        if !placed {
+            active.swap_remove(slot);
+        }
+    }
+
+    Ok(points)
+}
+
+/// Uniform by area: the radius is drawn as `sqrt` of a uniform in `[inner², outer²)`.
+pub fn annulus(args: &AnnulusArgs) -> Vec<OraclePoint> {
+    let mut rng = SplitMix64::new(args.random.seed);
+    let inner_2 = args.inner_radius * args.inner_radius;
+    let outer_2 = args.outer_radius * args.outer_radius;
+
+    (0..args.random.count)
+        .map(|_| {
+            let r = rng.range(inner_2, outer_2).sqrt();
+            let angle = TAU * rng.next_f64();
+            OraclePoint {
+                x: args.center_x + r * angle.cos(),
+                y: args.center_y + r * angle.sin(),
+            }
+        })
+        .collect()
+}
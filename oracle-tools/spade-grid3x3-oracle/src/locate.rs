@@ -0,0 +1,107 @@
+//! Spade's `locate` result for every input query, translated from handles to input indices.
+//!
+//! Queries are located in order on the Delaunay triangulation of the input points, so the
+//! `LastUsedVertexHintGenerator` carries its hint from one query to the next just as it would
+//! for the same sequence of .NET `Locate` calls. Which of the two directed edges an on-edge
+//! query reports depends on that walk; the hull edge of an outside query has the query and the
+//! outer face on its left and is not necessarily the closest one.
+
+use serde::Serialize;
+use spade::{DelaunayTriangulation, Point2, PositionInTriangulation, Triangulation};
+
+use crate::model::{OraclePoint, OracleTriangulationOutput};
+use crate::triangle_output;
+use crate::vertex_index::VertexIndex;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OracleLocateOutput {
+    #[serde(flatten)]
+    pub triangulation: OracleTriangulationOutput,
+    pub queries: Vec<OracleLocateQuery>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OracleLocateQuery {
+    pub x: f64,
+    pub y: f64,
+    pub position: OracleLocation,
+}
+
+/// A `PositionInTriangulation` with every vertex given by its input index.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(
+    tag = "kind",
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase"
+)]
+pub enum OracleLocation {
+    OnVertex {
+        vertex: usize,
+    },
+    /// The directed edge's origin, then its destination.
+    OnEdge {
+        edge: [usize; 2],
+    },
+    /// The face's vertices in counterclockwise order, starting where spade does.
+    OnFace {
+        face: [usize; 3],
+    },
+    /// A directed hull edge with the query on its left.
+    OutsideOfConvexHull {
+        edge: [usize; 2],
+    },
+    NoTriangulation,
+}
+
+pub fn locate_queries(
+    triangulation: &DelaunayTriangulation<Point2<f64>>,
+    index: &VertexIndex,
+    points: &[OraclePoint],
+    queries: &[OraclePoint],
+) -> OracleLocateOutput {
+    let queries = queries
+        .iter()
+        .map(|q| OracleLocateQuery {
+            x: q.x,
+            y: q.y,
+            position: locate(triangulation, index, Point2::new(q.x, q.y)),
+        })
+        .collect();
+
+    OracleLocateOutput {
+        triangulation: triangle_output(triangulation, index, points),
+        queries,
+    }
+}
+
+/// Spade's `locate` of `position`, in input-index terms.
+pub fn locate(
+    triangulation: &DelaunayTriangulation<Point2<f64>>,
+    index: &VertexIndex,
+    position: Point2<f64>,
+) -> OracleLocation {
+    let edge = |e| {
+        triangulation
+            .directed_edge(e)
+            .vertices()
+            .map(|v| index.input_index(v.fix()))
+    };
+    match triangulation.locate(position) {
+        PositionInTriangulation::OnVertex(v) => OracleLocation::OnVertex {
+            vertex: index.input_index(v),
+        },
+        PositionInTriangulation::OnEdge(e) => OracleLocation::OnEdge { edge: edge(e) },
+        PositionInTriangulation::OnFace(f) => OracleLocation::OnFace {
+            face: triangulation
+                .face(f)
+                .vertices()
+                .map(|v| index.input_index(v.fix())),
+        },
+        PositionInTriangulation::OutsideOfConvexHull(e) => {
+            OracleLocation::OutsideOfConvexHull { edge: edge(e) }
+        }
+        PositionInTriangulation::NoTriangulation => OracleLocation::NoTriangulation,
+    }
+}
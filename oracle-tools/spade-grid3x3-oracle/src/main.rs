@@ -1,42 +1,468 @@
+mod barycentric;
+mod batch;
+mod cdt;
+mod clipped_voronoi;
+mod coverage;
+mod dcel;
+mod domain;
+mod generate;
+mod locate;
+mod model;
+mod nearest;
+mod nni;
+mod quality;
+mod raster;
+mod refine;
+mod report;
+mod rng;
+mod trace;
+mod vertex_index;
+mod voronoi;
+
+use std::fmt::Write as _;
+use std::fs;
+use std::io::Write as _;
+use std::path::{Path, PathBuf};
+
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use serde::Serialize;
 use spade::{DelaunayTriangulation, Point2, Triangulation};
 
-fn main() -> Result<(), spade::InsertionError> {
-    let mut triangulation: DelaunayTriangulation<Point2<f64>> = DelaunayTriangulation::new();
+use crate::barycentric::barycentric;
+use crate::batch::{run_batch, BatchMode, BatchStatus};
+use crate::cdt::{build_cdt, cdt_output, render_cdt_text};
+use crate::clipped_voronoi::clipped_voronoi;
+use crate::coverage::{render_coverage_text, verify_coverage, OracleCoverageInput};
+use crate::dcel::dump_dcel;
+use crate::generate::Generator;
+use crate::locate::locate_queries;
+use crate::model::{OracleInput, OraclePoint, OracleTriangulationOutput};
+use crate::nearest::nearest_neighbors;
+use crate::nni::natural_neighbor;
+use crate::quality::{quality_report, render_quality_text, QualitySource};
+use crate::raster::{raster, raster_binary};
+use crate::refine::refine;
+use crate::report::{render_report_text, report};
+use crate::trace::trace_insertions;
+use crate::vertex_index::{insert_points, VertexIndex};
+use crate::voronoi::voronoi_output;
+
+/// Reference generator for the .NET `Spade.Tests.Validation` suite.
+#[derive(Parser)]
+#[command(version)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Build a Delaunay triangulation from an `OracleInput` JSON file.
+    Triangulate {
+        #[command(flatten)]
+        io: InputArgs,
+        #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+        format: OutputFormat,
+    },
+    /// Build a constrained Delaunay triangulation from the input `points` and `constraints`.
+    Cdt {
+        #[command(flatten)]
+        io: InputArgs,
+        #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+        format: OutputFormat,
+        /// Add constraints with `add_constraint_and_split`, splitting crossed constraints at
+        /// Steiner vertices.
+        #[arg(long)]
+        split: bool,
+    },
+    /// Refine the `cdt` triangulation with the input's `refinement` parameters, as JSON.
+    Refine {
+        #[command(flatten)]
+        io: InputArgs,
+    },
+    /// Angle, area, aspect ratio, edge length and vertex degree statistics of a triangulation.
+    Quality {
+        #[command(flatten)]
+        io: InputArgs,
+        #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+        format: OutputFormat,
+        /// The triangulation to measure.
+        #[arg(long, value_enum, default_value_t = QualitySource::Triangulate)]
+        of: QualitySource,
+        /// Number of histogram bins; vertex degrees always get one bin per value.
+        #[arg(long, default_value_t = 10)]
+        bins: usize,
+    },
+    /// Extract the Voronoi cell of every vertex as an `OracleVoronoiOutput` JSON.
+    Voronoi {
+        #[command(flatten)]
+        io: InputArgs,
+    },
+    /// Clip the Voronoi cell of every vertex to the convex domain polygon, as JSON.
+    ClippedVoronoi {
+        #[command(flatten)]
+        io: InputArgs,
+    },
+    /// Check that clipped Voronoi cells tile their domain without gaps or overlaps. Fails when
+    /// the check does.
+    VerifyCoverage {
+        /// JSON with `cells` of `generatorIndex` and `polygon`, from `clipped-voronoi` or .NET.
+        cells: PathBuf,
+        /// `OracleInput` to take the domain and points from, instead of the cells file.
+        #[arg(long)]
+        input: Option<PathBuf>,
+        /// Relative tolerance: areas by the domain area, distances by its square root.
+        #[arg(long, default_value_t = 1e-9)]
+        epsilon: f64,
+        /// Write the result to this file instead of stdout.
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+        #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+        format: OutputFormat,
+    },
+    /// Interpolate the input `values` at the input `queries` with natural neighbor
+    /// interpolation, as an `OracleNniOutput` JSON.
+    Nni {
+        #[command(flatten)]
+        io: InputArgs,
+        /// Also estimate vertex gradients and evaluate the C1 interpolant with this flatness
+        /// (0.5 is Sibson's C1, 1.0 spade's suggested default).
+        #[arg(long)]
+        flatness: Option<f64>,
+    },
+    /// Interpolate the input `values` at the input `queries` with barycentric interpolation,
+    /// reporting where each query was located, as JSON.
+    Barycentric {
+        #[command(flatten)]
+        io: InputArgs,
+    },
+    /// Interpolate the input `values` on every node of the input `grid` with natural neighbor
+    /// interpolation.
+    Raster {
+        #[command(flatten)]
+        io: InputArgs,
+        #[arg(long, value_enum, default_value_t = RasterFormat::Json)]
+        format: RasterFormat,
+    },
+    /// Locate every input query with spade's `locate`, in input-index terms, as JSON.
+    Locate {
+        #[command(flatten)]
+        io: InputArgs,
+    },
+    /// Find the nearest vertex to every input query with spade's `nearest_neighbor`, listing
+    /// all equally near inputs by brute force, as JSON.
+    Nearest {
+        #[command(flatten)]
+        io: InputArgs,
+    },
+    /// Dump every vertex, directed edge and face of the triangulation as JSON.
+    Dcel {
+        #[command(flatten)]
+        io: InputArgs,
+    },
+    /// Summarize how spade handled the input: duplicates, collinearity, shortest edge.
+    Report {
+        #[command(flatten)]
+        io: InputArgs,
+        #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+        format: OutputFormat,
+    },
+    /// Record the locate walk and edge flips of every insertion as JSON.
+    Trace {
+        #[command(flatten)]
+        io: InputArgs,
+    },
+    /// Run a command over every `*.json` input below a directory and write a manifest.
+    Batch {
+        /// Directory searched recursively for `OracleInput` JSON files.
+        input_dir: PathBuf,
+        /// Mirror directory for the results and `manifest.json`.
+        output_dir: PathBuf,
+        #[arg(long, value_enum, default_value_t = BatchMode::Triangulate)]
+        mode: BatchMode,
+    },
+    /// Write a generated `OracleInput` JSON file.
+    Generate {
+        #[command(subcommand)]
+        generator: Generator,
+        /// Write the result to this file instead of stdout.
+        #[arg(short, long, global = true)]
+        output: Option<PathBuf>,
+        /// Also triangulate the generated points and write the `OracleTriangulationOutput` JSON
+        /// to this file.
+        #[arg(long, global = true)]
+        triangulation: Option<PathBuf>,
+    },
+}
+
+#[derive(Args)]
+struct InputArgs {
+    /// Path to the `OracleInput` JSON file.
+    input: PathBuf,
+    /// Write the result to this file instead of stdout.
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum OutputFormat {
+    /// Human-readable listing.
+    Text,
+    /// JSON; for `triangulate` this is the `OracleTriangulationOutput` read by
+    /// `OracleJson.DeserializeTriangulation`.
+    Json,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum RasterFormat {
+    /// The grid, its `min` and `max` nodes and the `rows`, with `null` outside the hull.
+    Json,
+    /// `width` and `height` as little-endian `u32`, then the rows as little-endian `f64`.
+    Binary,
+}
 
-    let mut points = Vec::new();
-    for y in 0..=2 {
-        for x in 0..=2 {
-            points.push(Point2::new(x as f64, y as f64));
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    match Cli::parse().command {
+        Command::Triangulate { io, format } => {
+            let input = OracleInput::read_from_file(&io.input)?;
+            let (triangulation, index) = build_delaunay(&input);
+            let result = triangle_output(&triangulation, &index, &input.points);
+            let rendered = match format {
+                OutputFormat::Text => render_triangles_text(&result),
+                OutputFormat::Json => to_json(&result)?,
+            };
+            write_output(&rendered, io.output.as_deref())?;
+        }
+        Command::Cdt { io, format, split } => {
+            let input = OracleInput::read_from_file(&io.input)?;
+            let result = cdt_output(build_cdt(&input, split));
+            let rendered = match format {
+                OutputFormat::Text => render_cdt_text(&result),
+                OutputFormat::Json => to_json(&result)?,
+            };
+            write_output(&rendered, io.output.as_deref())?;
+        }
+        Command::Refine { io } => {
+            let input = OracleInput::read_from_file(&io.input)?;
+            write_output(&to_json(&refine(&input))?, io.output.as_deref())?;
+        }
+        Command::Quality {
+            io,
+            format,
+            of,
+            bins,
+        } => {
+            let input = OracleInput::read_from_file(&io.input)?;
+            let result = quality_report(&input, of, bins);
+            let rendered = match format {
+                OutputFormat::Text => render_quality_text(&result),
+                OutputFormat::Json => to_json(&result)?,
+            };
+            write_output(&rendered, io.output.as_deref())?;
+        }
+        Command::Voronoi { io } => {
+            let input = OracleInput::read_from_file(&io.input)?;
+            let (triangulation, index) = build_delaunay(&input);
+            let result = voronoi_output(&triangulation, &index, &input.points);
+            write_output(&to_json(&result)?, io.output.as_deref())?;
+        }
+        Command::ClippedVoronoi { io } => {
+            let input = OracleInput::read_from_file(&io.input)?;
+            let (triangulation, index) = build_delaunay(&input);
+            let result =
+                clipped_voronoi(&triangulation, &index, &input.points, input.domain.as_ref())?;
+            write_output(&to_json(&result)?, io.output.as_deref())?;
+        }
+        Command::VerifyCoverage {
+            cells,
+            input,
+            epsilon,
+            output,
+            format,
+        } => {
+            let cells = OracleCoverageInput::read_from_file(&cells)?;
+            let input = input
+                .map(|path| OracleInput::read_from_file(&path))
+                .transpose()?;
+            let result = verify_coverage(&cells, input.as_ref(), epsilon)?;
+            let rendered = match format {
+                OutputFormat::Text => render_coverage_text(&result),
+                OutputFormat::Json => to_json(&result)?,
+            };
+            write_output(&rendered, output.as_deref())?;
+            if !result.valid {
+                return Err(format!(
+                    "coverage check failed: {} violations, covered area {:e} of {:e}",
+                    result.violations.len(),
+                    result.covered_area,
+                    result.domain_area
+                )
+                .into());
+            }
+        }
+        Command::Nni { io, flatness } => {
+            let input = OracleInput::read_from_file(&io.input)?;
+            let (triangulation, index) = build_delaunay(&input);
+            let result = natural_neighbor(&triangulation, &index, &input, flatness)?;
+            write_output(&to_json(&result)?, io.output.as_deref())?;
+        }
+        Command::Barycentric { io } => {
+            let input = OracleInput::read_from_file(&io.input)?;
+            let (triangulation, index) = build_delaunay(&input);
+            let result = barycentric(&triangulation, &index, &input)?;
+            write_output(&to_json(&result)?, io.output.as_deref())?;
+        }
+        Command::Raster { io, format } => {
+            let input = OracleInput::read_from_file(&io.input)?;
+            let (triangulation, index) = build_delaunay(&input);
+            let result = raster(&triangulation, &index, &input)?;
+            match format {
+                RasterFormat::Json => write_output(&to_json(&result)?, io.output.as_deref())?,
+                RasterFormat::Binary => {
+                    write_binary_output(&raster_binary(&result)?, io.output.as_deref())?
+                }
+            }
+        }
+        Command::Locate { io } => {
+            let input = OracleInput::read_from_file(&io.input)?;
+            let (triangulation, index) = build_delaunay(&input);
+            let result = locate_queries(&triangulation, &index, &input.points, &input.queries);
+            write_output(&to_json(&result)?, io.output.as_deref())?;
+        }
+        Command::Nearest { io } => {
+            let input = OracleInput::read_from_file(&io.input)?;
+            let (triangulation, index) = build_delaunay(&input);
+            let result = nearest_neighbors(&triangulation, &index, &input.points, &input.queries);
+            write_output(&to_json(&result)?, io.output.as_deref())?;
+        }
+        Command::Dcel { io } => {
+            let input = OracleInput::read_from_file(&io.input)?;
+            let (triangulation, index) = build_delaunay(&input);
+            let result = dump_dcel(&triangulation, &index, &input.points);
+            write_output(&to_json(&result)?, io.output.as_deref())?;
+        }
+        Command::Report { io, format } => {
+            let input = OracleInput::read_from_file(&io.input)?;
+            let (triangulation, index) = build_delaunay(&input);
+            let result = report(&triangulation, &index, &input.points);
+            let rendered = match format {
+                OutputFormat::Text => render_report_text(&result),
+                OutputFormat::Json => to_json(&result)?,
+            };
+            write_output(&rendered, io.output.as_deref())?;
+        }
+        Command::Trace { io } => {
+            let input = OracleInput::read_from_file(&io.input)?;
+            let result = trace_insertions(&input.points);
+            write_output(&to_json(&result)?, io.output.as_deref())?;
+        }
+        Command::Batch {
+            input_dir,
+            output_dir,
+            mode,
+        } => {
+            let manifest = run_batch(&input_dir, &output_dir, mode)?;
+            let failed = manifest
+                .cases
+                .iter()
+                .filter(|case| case.status != BatchStatus::Ok)
+                .count();
+            eprintln!(
+                "{} cases, {} failed (spade {})",
+                manifest.cases.len(),
+                failed,
+                manifest.spade_version
+            );
+        }
+        Command::Generate {
+            generator,
+            output,
+            triangulation,
+        } => {
+            let input = generator.generate()?;
+            write_output(&to_json(&input)?, output.as_deref())?;
+            if let Some(path) = triangulation {
+                let (delaunay, index) = build_delaunay(&input);
+                let result = triangle_output(&delaunay, &index, &input.points);
+                write_output(&to_json(&result)?, Some(&path))?;
+            }
         }
     }
 
-    for p in &points {
-        triangulation.insert(*p)?;
-    }
+    Ok(())
+}
 
-    let mut triangles: Vec<[usize; 3]> = Vec::new();
+fn build_delaunay(input: &OracleInput) -> (DelaunayTriangulation<Point2<f64>>, VertexIndex) {
+    let mut triangulation = DelaunayTriangulation::new();
+    let index = insert_points(&mut triangulation, &input.points);
+    (triangulation, index)
+}
 
-    for face in triangulation.inner_faces() {
-        let vertices = face.vertices();
-        let mut idx = [0usize; 3];
+/// Collects the inner faces as sorted input-index triples, in sorted order.
+fn triangle_output<T>(
+    triangulation: &T,
+    index: &VertexIndex,
+    points: &[OraclePoint],
+) -> OracleTriangulationOutput
+where
+    T: Triangulation<Vertex = Point2<f64>>,
+{
+    let mut triangles: Vec<[usize; 3]> = triangulation
+        .inner_faces()
+        .map(|face| {
+            let mut idx = face.vertices().map(|v| index.input_index(v.fix()));
+            idx.sort();
+            idx
+        })
+        .collect();
 
-        for (i, v) in vertices.iter().enumerate() {
-            let pos = v.position();
-            let x = pos.x as usize;
-            let y = pos.y as usize;
-            idx[i] = y * 3 + x;
-        }
+    triangles.sort();
 
-        idx.sort();
-        triangles.push(idx);
+    OracleTriangulationOutput {
+        points: points.to_vec(),
+        triangles,
+        duplicates: index.duplicates().to_vec(),
+        rejected: index.rejected().to_vec(),
     }
+}
 
-    triangles.sort();
+fn render_triangles_text(output: &OracleTriangulationOutput) -> String {
+    let mut text = format!(
+        "{} triangles (indices into the {} input points):\n",
+        output.triangles.len(),
+        output.points.len()
+    );
+    for t in &output.triangles {
+        let _ = writeln!(text, "[{}, {}, {}]", t[0], t[1], t[2]);
+    }
+    for d in &output.duplicates {
+        let _ = writeln!(text, "duplicate: {} merged into {}", d.index, d.merged_into);
+    }
+    for r in &output.rejected {
+        let _ = writeln!(text, "rejected: {} ({:?})", r.index, r.reason);
+    }
+    text
+}
 
-    println!("3x3 grid triangles (indices into 0..8 in row-major order):");
-    for t in &triangles {
-        println!("[{}, {}, {}]", t[0], t[1], t[2]);
+fn to_json<T: Serialize>(value: &T) -> Result<String, serde_json::Error> {
+    let mut json = serde_json::to_string_pretty(value)?;
+    json.push('\n');
+    Ok(json)
+}
+
+fn write_binary_output(bytes: &[u8], path: Option<&Path>) -> std::io::Result<()> {
+    match path {
+        Some(path) => fs::write(path, bytes),
+        None => std::io::stdout().write_all(bytes),
     }
+}
 
-    Ok(())
+fn write_output(rendered: &str, path: Option<&Path>) -> std::io::Result<()> {
+    match path {
+        Some(path) => fs::write(path, rendered),
+        None => {
+            print!("{rendered}");
+            Ok(())
+        }
+    }
 }
@@ -0,0 +1,349 @@
+//! JSON records shared with the .NET validation suite.
+//!
+//! The shapes mirror `dotnet/tests/Spade.Tests/Validation/OracleModels.cs`; field names are
+//! camelCase on the wire so files can be exchanged without any hand-editing.
+
+use std::fs;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+/// Coordinates that are not finite are written as the strings `"NaN"`, `"Infinity"` and
+/// `"-Infinity"`, matching `JsonNumberHandling.AllowNamedFloatingPointLiterals` on the .NET side.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct OraclePoint {
+    #[serde(with = "named_float")]
+    pub x: f64,
+    #[serde(with = "named_float")]
+    pub y: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OracleDomainPolygon {
+    pub vertices: Vec<OraclePoint>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OracleDomain {
+    #[serde(rename = "type")]
+    pub kind: String,
+    #[serde(default)]
+    pub polygon: Option<OracleDomainPolygon>,
+    /// Hole rings inside `polygon`, used by `cdt`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub holes: Vec<OracleDomainPolygon>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OracleInput {
+    pub points: Vec<OraclePoint>,
+    #[serde(default)]
+    pub weights: Option<Vec<f64>>,
+    #[serde(default)]
+    pub domain: Option<OracleDomain>,
+    /// Constraint edges as pairs of indices into `points`, used by `cdt`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub constraints: Vec<[usize; 2]>,
+    /// Parameters for `refine`; spade's defaults when absent.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub refinement: Option<OracleRefinementParameters>,
+    /// One value per point to interpolate, used by `nni`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub values: Vec<f64>,
+    /// Query positions for `nni`, `barycentric`, `locate` and `nearest`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub queries: Vec<OraclePoint>,
+    /// Raster nodes for `raster`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub grid: Option<OracleGrid>,
+    /// Generator and parameters that produced `points`, for inputs written by `generate`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub generator: Option<serde_json::Value>,
+}
+
+impl OracleInput {
+    pub fn read_from_file(path: &Path) -> Result<Self, Box<dyn std::error::Error>> {
+        Self::parse(&fs::read_to_string(path)?, path)
+    }
+
+    /// Parses and validates `json`; `path` is only used in error messages.
+    pub fn parse(json: &str, path: &Path) -> Result<Self, Box<dyn std::error::Error>> {
+        let input: OracleInput = serde_json::from_str(json)?;
+
+        if let Some(weights) = &input.weights {
+            if weights.len() != input.points.len() {
+                return Err(format!(
+                    "{}: expected {} weights, found {}",
+                    path.display(),
+                    input.points.len(),
+                    weights.len()
+                )
+                .into());
+            }
+        }
+
+        if !input.values.is_empty() && input.values.len() != input.points.len() {
+            return Err(format!(
+                "{}: expected {} values, found {}",
+                path.display(),
+                input.points.len(),
+                input.values.len()
+            )
+            .into());
+        }
+
+        // Spade asserts on non-finite query positions instead of returning an error.
+        if let Some((i, q)) = input
+            .queries
+            .iter()
+            .enumerate()
+            .find(|(_, q)| !(q.x.is_finite() && q.y.is_finite()))
+        {
+            return Err(format!(
+                "{}: query {} ({}, {}) is not finite",
+                path.display(),
+                i,
+                q.x,
+                q.y
+            )
+            .into());
+        }
+
+        if let Some(grid) = &input.grid {
+            if grid.width == 0
+                || grid.height == 0
+                || !(grid.cell_size.is_finite() && grid.cell_size > 0.0)
+            {
+                return Err(format!(
+                    "{}: grid needs a positive width, height and cellSize",
+                    path.display()
+                )
+                .into());
+            }
+        }
+
+        if let Some(domain) = &input.domain {
+            if domain.polygon.is_none() && !domain.holes.is_empty() {
+                return Err(format!("{}: domain has holes but no polygon", path.display()).into());
+            }
+            let rings = domain.polygon.iter().chain(&domain.holes);
+            if let Some(ring) = rings.into_iter().find(|ring| ring.vertices.len() < 3) {
+                return Err(format!(
+                    "{}: domain ring with {} vertices, expected at least 3",
+                    path.display(),
+                    ring.vertices.len()
+                )
+                .into());
+            }
+        }
+
+        if let Some((i, c)) = input
+            .constraints
+            .iter()
+            .enumerate()
+            .find(|(_, c)| c.iter().any(|&v| v >= input.points.len()))
+        {
+            return Err(format!(
+                "{}: constraint {} {:?} refers to a point outside of the {} input points",
+                path.display(),
+                i,
+                c,
+                input.points.len()
+            )
+            .into());
+        }
+
+        Ok(input)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OracleTriangulationOutput {
+    pub points: Vec<OraclePoint>,
+    pub triangles: Vec<[usize; 3]>,
+    /// Inputs that spade merged into an earlier vertex at the same position.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub duplicates: Vec<OracleDuplicate>,
+    /// Inputs that spade rejected; they appear in no triangle.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub rejected: Vec<OracleRejection>,
+}
+
+/// An input point whose position was already present when it was inserted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OracleDuplicate {
+    pub index: usize,
+    pub merged_into: usize,
+}
+
+/// Mirrors spade's `RefinementParameters`. Absent fields take spade's defaults, which differ from
+/// the .NET defaults: no outer faces excluded, constraint edges may be split, and at most ten
+/// times the initial vertex count is added.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct OracleRefinementParameters {
+    pub angle_limit: OracleAngleLimit,
+    pub max_allowed_area: Option<f64>,
+    pub min_required_area: Option<f64>,
+    pub keep_constraint_edges: bool,
+    pub exclude_outer_faces: bool,
+    pub max_additional_vertices: Option<usize>,
+}
+
+impl Default for OracleRefinementParameters {
+    fn default() -> Self {
+        OracleRefinementParameters {
+            angle_limit: OracleAngleLimit::RadiusToShortestEdgeRatio(1.0),
+            max_allowed_area: None,
+            min_required_area: None,
+            keep_constraint_edges: false,
+            exclude_outer_faces: false,
+            max_additional_vertices: None,
+        }
+    }
+}
+
+/// `width` by `height` nodes, the node in column `ix` and row `iy` at
+/// `origin + (ix, iy) * cellSize`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OracleGrid {
+    pub origin: OraclePoint,
+    pub cell_size: f64,
+    pub width: usize,
+    pub height: usize,
+}
+
+/// One of spade's `AngleLimit` constructors, e.g. `{ "degrees": 30.0 }`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum OracleAngleLimit {
+    Degrees(f64),
+    Radians(f64),
+    RadiusToShortestEdgeRatio(f64),
+}
+
+/// An input point spade refused to insert. Spade checks `x` before `y`, so a point with two bad
+/// coordinates reports the reason for `x`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OracleRejection {
+    pub index: usize,
+    pub reason: OracleRejectionReason,
+}
+
+/// Mirrors `spade::InsertionError`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum OracleRejectionReason {
+    Nan,
+    TooSmall,
+    TooLarge,
+}
+
+impl From<spade::InsertionError> for OracleRejectionReason {
+    fn from(error: spade::InsertionError) -> Self {
+        match error {
+            spade::InsertionError::NAN => OracleRejectionReason::Nan,
+            spade::InsertionError::TooSmall => OracleRejectionReason::TooSmall,
+            spade::InsertionError::TooLarge => OracleRejectionReason::TooLarge,
+        }
+    }
+}
+
+/// What happened to one input point, in input order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OracleInsertion {
+    pub index: usize,
+    #[serde(flatten)]
+    pub status: OracleInsertionStatus,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(
+    tag = "status",
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase"
+)]
+pub enum OracleInsertionStatus {
+    /// Created vertex handle `vertex`.
+    Inserted {
+        vertex: usize,
+    },
+    /// Merged into the vertex created by input `merged_into`.
+    Duplicate {
+        merged_into: usize,
+    },
+    Nan,
+    TooSmall,
+    TooLarge,
+}
+
+mod named_float {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Repr {
+        Number(f64),
+        Named(String),
+    }
+
+    pub fn serialize<S: Serializer>(value: &f64, serializer: S) -> Result<S::Ok, S::Error> {
+        if value.is_nan() {
+            serializer.serialize_str("NaN")
+        } else if value.is_infinite() {
+            serializer.serialize_str(if *value > 0.0 {
+                "Infinity"
+            } else {
+                "-Infinity"
+            })
+        } else {
+            serializer.serialize_f64(*value)
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<f64, D::Error> {
+        match Repr::deserialize(deserializer)? {
+            Repr::Number(value) => Ok(value),
+            Repr::Named(name) if name == "NaN" => Ok(f64::NAN),
+            Repr::Named(name) if name == "Infinity" => Ok(f64::INFINITY),
+            Repr::Named(name) if name == "-Infinity" => Ok(f64::NEG_INFINITY),
+            Repr::Named(other) => Err(serde::de::Error::custom(format!(
+                "expected a number, \"NaN\", \"Infinity\" or \"-Infinity\", found \"{other}\""
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::OracleInput;
+
+    #[test]
+    fn rejects_non_finite_queries() {
+        let points = r#""points": [{ "x": 0, "y": 0 }, { "x": 1, "y": 0 }, { "x": 0, "y": 1 }]"#;
+        for query in [
+            r#"{ "x": "NaN", "y": 0.5 }"#,
+            r#"{ "x": 0.5, "y": "NaN" }"#,
+            r#"{ "x": "Infinity", "y": 0.5 }"#,
+            r#"{ "x": 0.5, "y": "-Infinity" }"#,
+        ] {
+            let json = format!(r#"{{ {points}, "queries": [{{ "x": 0.2, "y": 0.2 }}, {query}] }}"#);
+            let error = OracleInput::parse(&json, Path::new("queries.json")).unwrap_err();
+            assert!(
+                error.to_string().starts_with("queries.json: query 1 "),
+                "{error}"
+            );
+        }
+
+        let json = format!(r#"{{ {points}, "queries": [{{ "x": 0.2, "y": 0.2 }}] }}"#);
+        assert!(OracleInput::parse(&json, Path::new("queries.json")).is_ok());
+    }
+}
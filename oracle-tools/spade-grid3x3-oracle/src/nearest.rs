@@ -0,0 +1,128 @@
+//! Spade's `nearest_neighbor` for every input query, next to every equally near input point.
+//!
+//! The candidates come from a brute-force scan of the inputs that resolve to a vertex, with
+//! distances computed from the vertex positions like spade's `distance_2`, and are compared
+//! exactly. A merged duplicate is as near as the point it was merged into, so it is a candidate
+//! too, but not a tie. A port may pick any candidate, not necessarily the one spade picked.
+
+use serde::Serialize;
+use spade::{DelaunayTriangulation, Point2, Triangulation};
+
+use crate::model::{OraclePoint, OracleTriangulationOutput};
+use crate::triangle_output;
+use crate::vertex_index::VertexIndex;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OracleNearestOutput {
+    #[serde(flatten)]
+    pub triangulation: OracleTriangulationOutput,
+    pub queries: Vec<OracleNearestQuery>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OracleNearestQuery {
+    pub x: f64,
+    pub y: f64,
+    /// Input index of the vertex spade returned; absent when there are no vertices.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nearest: Option<usize>,
+    /// Squared distance to the nearest vertex; absent when there are no vertices.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub distance_2: Option<f64>,
+    /// Every input index at that minimal squared distance, ascending.
+    pub candidates: Vec<usize>,
+    /// The candidates resolve to more than one vertex; duplicates merged into the same vertex are
+    /// one candidate.
+    pub tie: bool,
+    /// `nearest` is one of the candidates.
+    pub matches_brute_force: bool,
+}
+
+pub fn nearest_neighbors(
+    triangulation: &DelaunayTriangulation<Point2<f64>>,
+    index: &VertexIndex,
+    points: &[OraclePoint],
+    queries: &[OraclePoint],
+) -> OracleNearestOutput {
+    let queries = queries
+        .iter()
+        .map(|q| {
+            let position = Point2::new(q.x, q.y);
+            let nearest = triangulation.nearest_neighbor(position);
+
+            let mut distance_2 = f64::INFINITY;
+            let mut candidates = Vec::new();
+            for i in 0..points.len() {
+                let Some(vertex) = index.vertex(i) else {
+                    continue;
+                };
+                let d = triangulation.vertex(vertex).position().distance_2(position);
+                if d < distance_2 {
+                    distance_2 = d;
+                    candidates.clear();
+                }
+                if d == distance_2 {
+                    candidates.push(i);
+                }
+            }
+
+            let mut vertices: Vec<usize> = candidates
+                .iter()
+                .filter_map(|&i| index.vertex(i))
+                .map(|v| v.index())
+                .collect();
+            vertices.sort_unstable();
+            vertices.dedup();
+
+            OracleNearestQuery {
+                x: q.x,
+                y: q.y,
+                distance_2: nearest.map(|v| v.position().distance_2(position)),
+                nearest: nearest.map(|v| index.input_index(v.fix())),
+                tie: vertices.len() > 1,
+                matches_brute_force: nearest.map_or(candidates.is_empty(), |v| {
+                    candidates.contains(&index.input_index(v.fix()))
+                }),
+                candidates,
+            }
+        })
+        .collect();
+
+    OracleNearestOutput {
+        triangulation: triangle_output(triangulation, index, points),
+        queries,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+    use crate::build_delaunay;
+    use crate::model::OracleInput;
+
+    #[test]
+    fn merged_duplicates_are_not_ties() {
+        let json = r#"{
+            "points": [{ "x": 0, "y": 0 }, { "x": 1, "y": 0 }, { "x": 0, "y": 1 }, { "x": 0, "y": 0 }],
+            "queries": [{ "x": 0.1, "y": 0.2 }, { "x": 0.5, "y": 0.5 }]
+        }"#;
+        let input = OracleInput::parse(json, Path::new("duplicate.json")).unwrap();
+        let (triangulation, index) = build_delaunay(&input);
+        let output = nearest_neighbors(&triangulation, &index, &input.points, &input.queries);
+
+        let single = &output.queries[0];
+        assert_eq!(single.nearest, Some(0));
+        assert_eq!(single.candidates, [0, 3]);
+        assert!(!single.tie);
+        assert!(single.matches_brute_force);
+
+        let tied = &output.queries[1];
+        assert_eq!(tied.candidates, [0, 1, 2, 3]);
+        assert!(tied.tie);
+        assert!(tied.matches_brute_force);
+    }
+}
@@ -0,0 +1,143 @@
+//! Natural neighbor interpolation with spade's `NaturalNeighbor`, in the `OracleNniOutput` shape
+//! read by `Spade.Advanced.Tests`.
+//!
+//! Spade replaces a vertex's data when the same position is inserted again, so a merged
+//! duplicate's value overrides the earlier one, as it does in the .NET test that inserts every
+//! point. Weights, however, are reported under the first input index of the merged vertex, the
+//! one that created it, not the duplicate whose value is used.
+//!
+//! Given a flatness, every query is also evaluated with spade's `interpolate_gradient`, using
+//! the gradients `estimate_gradients` derives from the same values. Those gradients are
+//! reported per vertex so a port can check its estimate and its C1 interpolant separately.
+
+use std::error::Error;
+
+use serde::Serialize;
+use spade::handles::VertexHandle;
+use spade::{DelaunayTriangulation, Point2, Triangulation};
+
+use crate::model::{OracleInput, OraclePoint};
+use crate::vertex_index::VertexIndex;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OracleNniOutput {
+    pub points: Vec<OraclePoint>,
+    pub values: Vec<f64>,
+    /// The `--flatness` the smooth values were computed with, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub flatness: Option<f64>,
+    /// With a flatness only: the estimated gradient of every vertex, in vertex handle order.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub gradients: Vec<OracleNniGradient>,
+    pub queries: Vec<OracleNniQuery>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OracleNniQuery {
+    pub x: f64,
+    pub y: f64,
+    /// Sibson coordinates in the clockwise order spade returns them; empty outside the convex
+    /// hull.
+    pub weights: Vec<OracleNniWeight>,
+    /// Absent outside the convex hull, where spade returns `None`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<f64>,
+    /// With a flatness only: the `interpolate_gradient` value; absent outside the convex hull.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub smooth_value: Option<f64>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OracleNniWeight {
+    pub point_index: usize,
+    pub weight: f64,
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OracleNniGradient {
+    pub point_index: usize,
+    pub x: f64,
+    pub y: f64,
+}
+
+pub fn natural_neighbor(
+    triangulation: &DelaunayTriangulation<Point2<f64>>,
+    index: &VertexIndex,
+    input: &OracleInput,
+    flatness: Option<f64>,
+) -> Result<OracleNniOutput, Box<dyn Error>> {
+    if input.values.is_empty() && !input.points.is_empty() {
+        return Err("nni needs one `values` entry per point".into());
+    }
+    let vertex_values = vertex_values(triangulation, index, &input.values);
+
+    let nn = triangulation.natural_neighbor();
+    let value = |v: VertexHandle<_>| vertex_values[v.fix().index()];
+    let gradient = flatness.map(|_| nn.estimate_gradients(value));
+    let gradients = match &gradient {
+        Some(gradient) => triangulation
+            .vertices()
+            .map(|v| {
+                let [x, y] = gradient(v);
+                OracleNniGradient {
+                    point_index: index.input_index(v.fix()),
+                    x,
+                    y,
+                }
+            })
+            .collect(),
+        None => Vec::new(),
+    };
+
+    let mut weights = Vec::new();
+    let queries = input
+        .queries
+        .iter()
+        .map(|q| {
+            let position = Point2::new(q.x, q.y);
+            nn.get_weights(position, &mut weights);
+            OracleNniQuery {
+                x: q.x,
+                y: q.y,
+                weights: weights
+                    .iter()
+                    .map(|&(vertex, weight)| OracleNniWeight {
+                        point_index: index.input_index(vertex),
+                        weight,
+                    })
+                    .collect(),
+                value: nn.interpolate(value, position),
+                smooth_value: flatness.zip(gradient.as_ref()).and_then(|(f, gradient)| {
+                    nn.interpolate_gradient(value, gradient, f, position)
+                }),
+            }
+        })
+        .collect();
+
+    Ok(OracleNniOutput {
+        points: input.points.clone(),
+        values: input.values.clone(),
+        flatness,
+        gradients,
+        queries,
+    })
+}
+
+/// The value of every vertex, by handle index: the last input that resolved to it wins.
+pub fn vertex_values(
+    triangulation: &DelaunayTriangulation<Point2<f64>>,
+    index: &VertexIndex,
+    values: &[f64],
+) -> Vec<f64> {
+    let mut vertex_values = vec![0.0; triangulation.num_vertices()];
+    for (i, &value) in values.iter().enumerate() {
+        if let Some(vertex) = index.vertex(i) {
+            vertex_values[vertex.index()] = value;
+        }
+    }
+    vertex_values
+}
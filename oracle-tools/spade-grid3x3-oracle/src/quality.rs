@@ -0,0 +1,235 @@
+//! Mesh quality statistics of a triangle list, for comparing meshes whose topology differs.
+//!
+//! Everything is computed from the output `points` and `triangles` alone, in the order they
+//! appear, so the .NET side can recompute the same numbers from its own output and compare them
+//! within a tolerance. Per triangle with side lengths `a`, `b`, `c` and area `A`:
+//!
+//! - each angle is `atan2(|cross|, dot)` of the two edge vectors leaving its corner, in degrees;
+//! - the area is half the absolute cross product of `b - a` and `c - a`;
+//! - the aspect ratio is the circumradius over twice the inradius, `abc * s / (8 * A^2)` with
+//!   `s` the semi-perimeter, which is 1 for an equilateral triangle. Triangles with zero area
+//!   have no aspect ratio and are only counted.
+//!
+//! Edges are the distinct sorted vertex pairs of the triangles and a vertex's degree is the
+//! number of those edges it belongs to; vertices in no triangle are left out.
+
+use std::collections::BTreeSet;
+use std::fmt::Write as _;
+
+use clap::ValueEnum;
+use serde::Serialize;
+
+use crate::cdt::{build_cdt, cdt_output};
+use crate::model::{OracleInput, OraclePoint};
+use crate::refine::refine;
+use crate::{build_delaunay, triangle_output};
+
+/// The triangulation the statistics are computed for; each is built like the subcommand of
+/// the same name (`cdt-split` is `cdt --split`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum QualitySource {
+    Triangulate,
+    Cdt,
+    CdtSplit,
+    Refine,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OracleQualityReport {
+    pub source: QualitySource,
+    pub triangle_count: usize,
+    pub degenerate_triangle_count: usize,
+    pub angles: OracleStatistics,
+    pub areas: OracleStatistics,
+    pub aspect_ratios: OracleStatistics,
+    pub edge_lengths: OracleStatistics,
+    pub vertex_degrees: OracleStatistics,
+}
+
+/// `min`, `max` and `mean` are absent when there are no values.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OracleStatistics {
+    pub count: usize,
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+    pub mean: Option<f64>,
+    pub histogram: OracleHistogram,
+}
+
+/// `counts[i]` holds the values in `[lower + i * width, lower + (i + 1) * width)` with
+/// `width = (upper - lower) / counts.len()`; the last bin also holds `upper`. When
+/// `lower == upper` every value is in the first bin.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OracleHistogram {
+    pub lower: f64,
+    pub upper: f64,
+    pub counts: Vec<usize>,
+}
+
+pub fn quality_report(
+    input: &OracleInput,
+    source: QualitySource,
+    bins: usize,
+) -> OracleQualityReport {
+    let (points, triangles) = match source {
+        QualitySource::Triangulate => {
+            let (triangulation, index) = build_delaunay(input);
+            let output = triangle_output(&triangulation, &index, &input.points);
+            (output.points, output.triangles)
+        }
+        QualitySource::Cdt | QualitySource::CdtSplit => {
+            let output = cdt_output(build_cdt(input, source == QualitySource::CdtSplit));
+            (output.triangulation.points, output.triangulation.triangles)
+        }
+        QualitySource::Refine => {
+            // Faces spade excluded as outer faces are not part of the refined mesh.
+            let output = refine(input);
+            let excluded: BTreeSet<_> = output.excluded_faces.into_iter().collect();
+            let triangles = output
+                .triangulation
+                .triangles
+                .into_iter()
+                .filter(|t| !excluded.contains(t))
+                .collect();
+            (output.triangulation.points, triangles)
+        }
+    };
+    quality(source, &points, &triangles, bins)
+}
+
+pub fn quality(
+    source: QualitySource,
+    points: &[OraclePoint],
+    triangles: &[[usize; 3]],
+    bins: usize,
+) -> OracleQualityReport {
+    let mut angles = Vec::with_capacity(triangles.len() * 3);
+    let mut areas = Vec::with_capacity(triangles.len());
+    let mut aspect_ratios = Vec::with_capacity(triangles.len());
+    let mut edges = BTreeSet::new();
+
+    for &[i, j, k] in triangles {
+        let [a, b, c] = [i, j, k].map(|v| (points[v].x, points[v].y));
+        for (corner, p, q) in [(a, b, c), (b, c, a), (c, a, b)] {
+            angles.push(angle(corner, p, q));
+        }
+
+        let area = cross(a, b, c).abs() / 2.0;
+        areas.push(area);
+        if area > 0.0 {
+            let [ab, bc, ca] = [distance(a, b), distance(b, c), distance(c, a)];
+            let s = (ab + bc + ca) / 2.0;
+            aspect_ratios.push(ab * bc * ca * s / (8.0 * area * area));
+        }
+
+        for [u, v] in [[i, j], [j, k], [k, i]] {
+            edges.insert([u.min(v), u.max(v)]);
+        }
+    }
+
+    let mut degrees = vec![0usize; points.len()];
+    let edge_lengths: Vec<f64> = edges
+        .iter()
+        .map(|&[u, v]| {
+            degrees[u] += 1;
+            degrees[v] += 1;
+            distance((points[u].x, points[u].y), (points[v].x, points[v].y))
+        })
+        .collect();
+    let degrees: Vec<f64> = degrees
+        .into_iter()
+        .filter(|&d| d > 0)
+        .map(|d| d as f64)
+        .collect();
+
+    // Degrees get one bin per integer value.
+    let degree_range = range(&degrees).map(|(min, max)| (min, max + 1.0));
+    let degree_bins = degree_range.map_or(1, |(min, max)| (max - min) as usize);
+
+    OracleQualityReport {
+        source,
+        triangle_count: triangles.len(),
+        degenerate_triangle_count: triangles.len() - aspect_ratios.len(),
+        angles: statistics(&angles, Some((0.0, 180.0)), bins),
+        aspect_ratios: statistics(&aspect_ratios, range(&aspect_ratios), bins),
+        areas: statistics(&areas, range(&areas), bins),
+        edge_lengths: statistics(&edge_lengths, range(&edge_lengths), bins),
+        vertex_degrees: statistics(&degrees, degree_range, degree_bins),
+    }
+}
+
+fn statistics(values: &[f64], bounds: Option<(f64, f64)>, bins: usize) -> OracleStatistics {
+    let (lower, upper) = bounds.unwrap_or((0.0, 0.0));
+    let bins = bins.max(1);
+    let mut counts = vec![0; bins];
+    for &v in values {
+        let bin = if upper > lower {
+            ((v - lower) / (upper - lower) * bins as f64) as usize
+        } else {
+            0
+        };
+        counts[bin.min(bins - 1)] += 1;
+    }
+
+    let limits = range(values);
+    OracleStatistics {
+        count: values.len(),
+        min: limits.map(|(min, _)| min),
+        max: limits.map(|(_, max)| max),
+        mean: (!values.is_empty()).then(|| values.iter().sum::<f64>() / values.len() as f64),
+        histogram: OracleHistogram {
+            lower,
+            upper,
+            counts,
+        },
+    }
+}
+
+fn range(values: &[f64]) -> Option<(f64, f64)> {
+    let min = values.iter().copied().min_by(f64::total_cmp)?;
+    let max = values.iter().copied().max_by(f64::total_cmp)?;
+    Some((min, max))
+}
+
+/// The angle at `corner` between the edges to `p` and `q`, in degrees.
+fn angle(corner: (f64, f64), p: (f64, f64), q: (f64, f64)) -> f64 {
+    let u = (p.0 - corner.0, p.1 - corner.1);
+    let v = (q.0 - corner.0, q.1 - corner.1);
+    let cross = u.0 * v.1 - u.1 * v.0;
+    let dot = u.0 * v.0 + u.1 * v.1;
+    cross.abs().atan2(dot).to_degrees()
+}
+
+fn cross(a: (f64, f64), b: (f64, f64), c: (f64, f64)) -> f64 {
+    (b.0 - a.0) * (c.1 - a.1) - (b.1 - a.1) * (c.0 - a.0)
+}
+
+fn distance(a: (f64, f64), b: (f64, f64)) -> f64 {
+    let (dx, dy) = (b.0 - a.0, b.1 - a.1);
+    (dx * dx + dy * dy).sqrt()
+}
+
+pub fn render_quality_text(report: &OracleQualityReport) -> String {
+    let mut text = format!(
+        "{} triangles ({} degenerate)\n",
+        report.triangle_count, report.degenerate_triangle_count
+    );
+    for (name, s) in [
+        ("angle", &report.angles),
+        ("area", &report.areas),
+        ("aspect ratio", &report.aspect_ratios),
+        ("edge length", &report.edge_lengths),
+        ("vertex degree", &report.vertex_degrees),
+    ] {
+        let _ = write!(text, "{name}: {} values", s.count);
+        if let (Some(min), Some(max), Some(mean)) = (s.min, s.max, s.mean) {
+            let _ = write!(text, ", min {min:e}, max {max:e}, mean {mean:e}");
+        }
+        let _ = writeln!(text, ", histogram {:?}", s.histogram.counts);
+    }
+    text
+}
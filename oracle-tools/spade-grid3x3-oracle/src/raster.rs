@@ -0,0 +1,84 @@
+//! Exact natural neighbor values on every node of the input `grid`, as a reference for the .NET
+//! `NaturalNeighborGrid2D` interpolators.
+//!
+//! Nodes are evaluated with spade's `interpolate`, like `nni` queries. Rows run from the lowest
+//! `y` upwards and each row from the lowest `x`, which is the `[iy, ix]` layout of the .NET
+//! grids when called with the reported `min` and `max`. Nodes outside the convex hull have no
+//! value: `null` in JSON and NaN in the binary format, the .NET default `outsideValue`.
+
+use std::error::Error;
+
+use serde::Serialize;
+use spade::{DelaunayTriangulation, Point2};
+
+use crate::model::{OracleGrid, OracleInput, OraclePoint};
+use crate::nni::vertex_values;
+use crate::vertex_index::VertexIndex;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OracleRasterOutput {
+    /// The samples, as in the `nni` output.
+    pub points: Vec<OraclePoint>,
+    pub values: Vec<f64>,
+    pub grid: OracleGrid,
+    /// The first node, the .NET `min`.
+    pub min: OraclePoint,
+    /// The last node, the .NET `max`.
+    pub max: OraclePoint,
+    /// `height` rows of `width` values.
+    pub rows: Vec<Vec<Option<f64>>>,
+}
+
+pub fn raster(
+    triangulation: &DelaunayTriangulation<Point2<f64>>,
+    index: &VertexIndex,
+    input: &OracleInput,
+) -> Result<OracleRasterOutput, Box<dyn Error>> {
+    let grid = input.grid.ok_or("raster needs a `grid`")?;
+    if input.values.is_empty() && !input.points.is_empty() {
+        return Err("raster needs one `values` entry per point".into());
+    }
+    let vertex_values = vertex_values(triangulation, index, &input.values);
+
+    let node = |ix: usize, iy: usize| {
+        Point2::new(
+            grid.origin.x + ix as f64 * grid.cell_size,
+            grid.origin.y + iy as f64 * grid.cell_size,
+        )
+    };
+    let nn = triangulation.natural_neighbor();
+    let rows = (0..grid.height)
+        .map(|iy| {
+            (0..grid.width)
+                .map(|ix| nn.interpolate(|v| vertex_values[v.fix().index()], node(ix, iy)))
+                .collect()
+        })
+        .collect();
+
+    let last = node(grid.width - 1, grid.height - 1);
+    Ok(OracleRasterOutput {
+        points: input.points.clone(),
+        values: input.values.clone(),
+        grid,
+        min: grid.origin,
+        max: OraclePoint {
+            x: last.x,
+            y: last.y,
+        },
+        rows,
+    })
+}
+
+/// `width` and `height` as little-endian `u32`, then the rows as little-endian `f64`, with NaN
+/// for nodes outside the convex hull.
+pub fn raster_binary(output: &OracleRasterOutput) -> Result<Vec<u8>, Box<dyn Error>> {
+    let mut bytes = Vec::with_capacity(8 + 8 * output.grid.width * output.grid.height);
+    for size in [output.grid.width, output.grid.height] {
+        bytes.extend_from_slice(&u32::try_from(size)?.to_le_bytes());
+    }
+    for value in output.rows.iter().flatten() {
+        bytes.extend_from_slice(&value.unwrap_or(f64::NAN).to_le_bytes());
+    }
+    Ok(bytes)
+}
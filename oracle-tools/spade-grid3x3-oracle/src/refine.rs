@@ -0,0 +1,139 @@
+//! Delaunay refinement of the CDT built by `cdt`, with spade's `RefinementParameters`.
+//!
+//! The CDT is built exactly as `cdt` does (points, constraints, then domain rings), refined with
+//! the input's `refinement` parameters, and reported in terms of the refined mesh. Refinement
+//! only ever inserts vertices, so the vertices it adds are numbered after the input and ring
+//! points, in the order spade created them, and appended to `points`.
+
+use std::collections::HashSet;
+
+use serde::Serialize;
+use spade::handles::FixedVertexHandle;
+use spade::{AngleLimit, RefinementParameters, Triangulation};
+
+use crate::cdt::{build_cdt, cdt_edges, CdtBuild, OracleCdtEdge, OracleConstraintResult};
+use crate::model::{
+    OracleAngleLimit, OracleInput, OraclePoint, OracleRefinementParameters,
+    OracleTriangulationOutput,
+};
+use crate::triangle_output;
+
+/// A superset of `OracleTriangulationOutput`. `excludedFaces` and `refinementComplete` mirror
+/// spade's `RefinementResult`; `addedVertices` and `reachedVertexLimit` mirror the .NET one.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OracleRefinementOutput {
+    #[serde(flatten)]
+    pub triangulation: OracleTriangulationOutput,
+    /// The parameters actually used, with spade's defaults filled in. Unset area limits stay
+    /// `null`, since spade has no default for them.
+    pub parameters: OracleRefinementParameters,
+    pub edges: Vec<OracleCdtEdge>,
+    /// Outcome of every input constraint and domain ring edge before refinement, as in `cdt`.
+    pub constraints: Vec<OracleConstraintResult>,
+    /// Vertices inserted by refinement; also appended to `points`.
+    pub steiner_points: Vec<OracleRefinementPoint>,
+    /// Triangles of the refined mesh that spade excluded as outer faces, in sorted order. Empty
+    /// unless `excludeOuterFaces` is set.
+    pub excluded_faces: Vec<[usize; 3]>,
+    pub refinement_complete: bool,
+    pub added_vertices: usize,
+    pub reached_vertex_limit: bool,
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OracleRefinementPoint {
+    pub index: usize,
+    pub x: f64,
+    pub y: f64,
+}
+
+pub fn refine(input: &OracleInput) -> OracleRefinementOutput {
+    let CdtBuild {
+        mut cdt,
+        mut index,
+        mut points,
+        constraints,
+        ..
+    } = build_cdt(input, false);
+
+    let mut parameters = input.refinement.clone().unwrap_or_default();
+    // Spade's default limit, counted from the vertices present when `refine` is called.
+    parameters.max_additional_vertices = Some(
+        parameters
+            .max_additional_vertices
+            .unwrap_or(10 * cdt.num_vertices()),
+    );
+
+    let result = cdt.refine(spade_parameters(&parameters));
+
+    let first_added = index.vertex_count();
+    let steiner_points: Vec<OracleRefinementPoint> = (first_added..cdt.num_vertices())
+        .map(|v| {
+            let vertex = cdt.vertex(FixedVertexHandle::from_index(v));
+            let position = vertex.position();
+            OracleRefinementPoint {
+                index: index.record_steiner(vertex.fix()),
+                x: position.x,
+                y: position.y,
+            }
+        })
+        .collect();
+    points.extend(
+        steiner_points
+            .iter()
+            .map(|p| OraclePoint { x: p.x, y: p.y }),
+    );
+
+    let excluded: HashSet<_> = result.excluded_faces.iter().copied().collect();
+    let mut excluded_faces: Vec<[usize; 3]> = cdt
+        .inner_faces()
+        .filter(|face| excluded.contains(&face.fix()))
+        .map(|face| {
+            let mut triangle = face.vertices().map(|v| index.input_index(v.fix()));
+            triangle.sort();
+            triangle
+        })
+        .collect();
+    excluded_faces.sort();
+
+    OracleRefinementOutput {
+        triangulation: triangle_output(&cdt, &index, &points),
+        parameters,
+        edges: cdt_edges(&cdt, &index),
+        constraints,
+        added_vertices: steiner_points.len(),
+        steiner_points,
+        excluded_faces,
+        refinement_complete: result.refinement_complete,
+        reached_vertex_limit: !result.refinement_complete,
+    }
+}
+
+fn spade_parameters(parameters: &OracleRefinementParameters) -> RefinementParameters<f64> {
+    let angle_limit = match parameters.angle_limit {
+        OracleAngleLimit::Degrees(degrees) => AngleLimit::from_deg(degrees),
+        OracleAngleLimit::Radians(radians) => AngleLimit::from_rad(radians),
+        OracleAngleLimit::RadiusToShortestEdgeRatio(ratio) => {
+            AngleLimit::from_radius_to_shortest_edge_ratio(ratio)
+        }
+    };
+
+    let mut spade = RefinementParameters::new()
+        .with_angle_limit(angle_limit)
+        .exclude_outer_faces(parameters.exclude_outer_faces);
+    if let Some(area) = parameters.max_allowed_area {
+        spade = spade.with_max_allowed_area(area);
+    }
+    if let Some(area) = parameters.min_required_area {
+        spade = spade.with_min_required_area(area);
+    }
+    if parameters.keep_constraint_edges {
+        spade = spade.keep_constraint_edges();
+    }
+    if let Some(count) = parameters.max_additional_vertices {
+        spade = spade.with_max_additional_vertices(count);
+    }
+    spade
+}
@@ -0,0 +1,92 @@
+//! Summary of how spade handled an input: merged duplicates, rejected points, the degenerate 1-D
+//! case, and the shortest surviving edge (which shows whether near-duplicates were kept as
+//! separate vertices).
+
+use std::fmt::Write as _;
+
+use serde::Serialize;
+use spade::{Point2, Triangulation};
+
+use crate::model::{OracleDuplicate, OracleInsertion, OraclePoint, OracleRejection};
+use crate::vertex_index::VertexIndex;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OracleReport {
+    pub input_count: usize,
+    pub vertex_count: usize,
+    pub duplicates: Vec<OracleDuplicate>,
+    pub rejected: Vec<OracleRejection>,
+    /// Spade keeps collinear inputs as a chain of edges with no inner faces.
+    pub all_vertices_on_line: bool,
+    pub inner_face_count: usize,
+    pub undirected_edge_count: usize,
+    pub convex_hull_size: usize,
+    pub shortest_edge: Option<OracleEdgeLength>,
+    /// Outcome of every input point, in input order.
+    pub insertions: Vec<OracleInsertion>,
+}
+
+/// An edge between two input points, by input index.
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OracleEdgeLength {
+    pub from: usize,
+    pub to: usize,
+    pub length: f64,
+}
+
+pub fn report<T>(triangulation: &T, index: &VertexIndex, points: &[OraclePoint]) -> OracleReport
+where
+    T: Triangulation<Vertex = Point2<f64>>,
+{
+    let shortest_edge = triangulation
+        .undirected_edges()
+        .map(|edge| {
+            let [from, to] = edge.vertices().map(|v| index.input_index(v.fix()));
+            let length = edge.length_2().sqrt();
+            OracleEdgeLength { from, to, length }
+        })
+        .min_by(|a, b| a.length.total_cmp(&b.length));
+
+    OracleReport {
+        input_count: points.len(),
+        vertex_count: triangulation.num_vertices(),
+        duplicates: index.duplicates().to_vec(),
+        rejected: index.rejected().to_vec(),
+        all_vertices_on_line: triangulation.all_vertices_on_line(),
+        inner_face_count: triangulation.num_inner_faces(),
+        undirected_edge_count: triangulation.num_undirected_edges(),
+        convex_hull_size: triangulation.convex_hull_size(),
+        shortest_edge,
+        insertions: index.insertions().to_vec(),
+    }
+}
+
+pub fn render_report_text(report: &OracleReport) -> String {
+    let mut text = format!(
+        "{} inputs, {} vertices, {} inner faces, {} edges, convex hull of {}\n",
+        report.input_count,
+        report.vertex_count,
+        report.inner_face_count,
+        report.undirected_edge_count,
+        report.convex_hull_size
+    );
+    if report.all_vertices_on_line {
+        text.push_str("all vertices on line\n");
+    }
+    if let Some(e) = report.shortest_edge {
+        let _ = writeln!(
+            text,
+            "shortest edge: {} - {} ({:e})",
+            e.from, e.to, e.length
+        );
+    }
+    for d in &report.duplicates {
+        let _ = writeln!(text, "duplicate: {} merged into {}", d.index, d.merged_into);
+    }
+    for r in &report.rejected {
+        let _ = writeln!(text, "rejected: {} ({:?})", r.index, r.reason);
+    }
+    text
+}
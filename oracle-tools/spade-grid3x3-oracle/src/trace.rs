@@ -0,0 +1,517 @@
+//! Per-insertion trace of spade's locate walk and edge legalization.
+//!
+//! Spade keeps `locate_with_hint_fixed_core` and `legalize_edge` private, so both are replayed on a
+//! copy of the triangulation taken right before each insertion, using only public handle queries
+//! and the same `robust` predicates. Each replay is checked against what spade actually did: the
+//! replayed locate must agree with `locate_with_hint` from the same hint, and the replayed flips
+//! must reproduce spade's triangles after the insertion, with every flipped edge keeping its
+//! undirected handle. `replayMatchesSpade` records the outcome so a mismatch shows up in the
+//! output instead of being trusted silently.
+//!
+//! All handles are spade's fixed handle indices, as in the `dcel` dump. Copying the triangulation
+//! for every insertion is quadratic, so this is meant for small scenarios.
+
+use std::collections::{BTreeSet, HashMap};
+
+use serde::Serialize;
+use spade::handles::{FixedDirectedEdgeHandle, FixedVertexHandle};
+use spade::{DelaunayTriangulation, Point2, PositionInTriangulation, Triangulation};
+
+use crate::model::{OraclePoint, OracleRejection};
+
+type Delaunay = DelaunayTriangulation<Point2<f64>>;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OracleTraceOutput {
+    pub points: Vec<OraclePoint>,
+    pub insertions: Vec<OracleInsertionTrace>,
+    /// Inputs that spade rejected; they have no trace and do not change the hint.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub rejected: Vec<OracleRejection>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OracleInsertionTrace {
+    /// Index of the inserted point in the input.
+    pub index: usize,
+    /// Vertex handle returned by `insert`.
+    pub vertex: usize,
+    /// Vertex the locate walk started from, or `None` if spade did not walk (fewer than two
+    /// vertices, or all vertices on a line).
+    pub hint: Option<usize>,
+    /// Vertices visited by `walk_to_nearest_neighbor`, starting with the hint.
+    pub nearest_neighbor_walk: Vec<usize>,
+    /// Rotation edges visited by the locate walk, in order.
+    pub locate_walk: Vec<OracleLocateStep>,
+    pub position: OraclePosition,
+    /// Edge flips performed by legalization, in order.
+    pub flips: Vec<OracleFlip>,
+    pub replay_matches_spade: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OracleLocateStep {
+    /// The edge `e0` the walk rotates around (its origin is the rotation vertex).
+    pub edge: usize,
+    /// The inner face of the segment tested at this step, if the walk got that far.
+    pub face: Option<usize>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum OraclePosition {
+    OnVertex { vertex: usize },
+    OnEdge { edge: usize },
+    OnFace { face: usize },
+    OutsideOfConvexHull { edge: usize },
+    NoTriangulation,
+}
+
+impl From<PositionInTriangulation> for OraclePosition {
+    fn from(position: PositionInTriangulation) -> Self {
+        match position {
+            PositionInTriangulation::OnVertex(v) => OraclePosition::OnVertex { vertex: v.index() },
+            PositionInTriangulation::OnEdge(e) => OraclePosition::OnEdge { edge: e.index() },
+            PositionInTriangulation::OnFace(f) => OraclePosition::OnFace { face: f.index() },
+            PositionInTriangulation::OutsideOfConvexHull(e) => {
+                OraclePosition::OutsideOfConvexHull { edge: e.index() }
+            }
+            PositionInTriangulation::NoTriangulation => OraclePosition::NoTriangulation,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OracleFlip {
+    /// Undirected edge handle; spade keeps the handle when flipping.
+    pub edge: usize,
+    pub before: [usize; 2],
+    pub after: [usize; 2],
+}
+
+pub fn trace_insertions(points: &[OraclePoint]) -> OracleTraceOutput {
+    let mut triangulation = Delaunay::new();
+    // `LastUsedVertexHintGenerator` is notified with the handle returned by every insertion,
+    // so that handle is the hint of the next one.
+    let mut hint = FixedVertexHandle::from_index(0);
+    let mut insertions = Vec::with_capacity(points.len());
+    let mut rejected = Vec::new();
+
+    for (i, p) in points.iter().enumerate() {
+        let position = Point2::new(p.x, p.y);
+        let before = triangulation.clone();
+        let handle = match triangulation.insert(position) {
+            Ok(handle) => handle,
+            Err(error) => {
+                rejected.push(OracleRejection {
+                    index: i,
+                    reason: error.into(),
+                });
+                continue;
+            }
+        };
+
+        insertions.push(trace_insertion(
+            i,
+            &before,
+            &triangulation,
+            position,
+            hint,
+            handle,
+        ));
+        hint = handle;
+    }
+
+    OracleTraceOutput {
+        points: points.to_vec(),
+        insertions,
+        rejected,
+    }
+}
+
+fn trace_insertion(
+    index: usize,
+    before: &Delaunay,
+    after: &Delaunay,
+    position: Point2<f64>,
+    hint: FixedVertexHandle,
+    handle: FixedVertexHandle,
+) -> OracleInsertionTrace {
+    let walks = before.num_vertices() >= 2 && !before.all_vertices_on_line();
+    let spade_position = before.locate_with_hint(position, hint);
+
+    let (nearest_neighbor_walk, locate_walk, replayed_position) = if walks {
+        replay_locate(before, position, hint)
+    } else {
+        (Vec::new(), Vec::new(), Some(spade_position))
+    };
+
+    let is_new_vertex = handle.index() == before.num_vertices();
+    let mut replay = Replay::new(before, position);
+    if is_new_vertex {
+        replay.insert(spade_position);
+    }
+
+    let flips: Vec<OracleFlip> = replay
+        .flips
+        .iter()
+        .filter_map(|&(before_edge, after_edge)| {
+            let edge =
+                before.get_edge_from_neighbors(vertex(before_edge[0]), vertex(before_edge[1]))?;
+            Some(OracleFlip {
+                edge: edge.as_undirected().fix().index(),
+                before: before_edge,
+                after: after_edge,
+            })
+        })
+        .collect();
+
+    let flips_keep_handles = flips.len() == replay.flips.len()
+        && flips.iter().all(|flip| {
+            after
+                .get_edge_from_neighbors(vertex(flip.after[0]), vertex(flip.after[1]))
+                .map(|e| e.as_undirected().fix().index())
+                == Some(flip.edge)
+        });
+
+    OracleInsertionTrace {
+        index,
+        vertex: handle.index(),
+        hint: walks.then_some(hint.index()),
+        nearest_neighbor_walk,
+        locate_walk,
+        position: spade_position.into(),
+        flips,
+        replay_matches_spade: replayed_position == Some(spade_position)
+            && replay.apex.triangles() == ApexMap::from_triangulation(after).triangles()
+            && flips_keep_handles,
+    }
+}
+
+fn vertex(index: usize) -> FixedVertexHandle {
+    FixedVertexHandle::from_index(index)
+}
+
+/// Replays `locate_with_hint_fixed_core` for a triangulation that is not degenerate.
+///
+/// Returns the nearest neighbor walk, the rotation steps and the located position, or `None` for
+/// the position if the walk did not terminate.
+fn replay_locate(
+    triangulation: &Delaunay,
+    target: Point2<f64>,
+    hint: FixedVertexHandle,
+) -> (
+    Vec<usize>,
+    Vec<OracleLocateStep>,
+    Option<PositionInTriangulation>,
+) {
+    let start = if hint.index() < triangulation.num_vertices() {
+        hint
+    } else {
+        vertex(0)
+    };
+
+    let mut current = triangulation.vertex(start);
+    let mut nearest_neighbor_walk = vec![current.fix().index()];
+    if current.position() != target {
+        let mut best_distance = target.distance_2(current.position());
+        while let Some((next, distance)) = current
+            .out_edges()
+            .map(|e| e.to())
+            .map(|n| (n, n.position().distance_2(target)))
+            .find(|&(_, distance)| distance < best_distance)
+        {
+            best_distance = distance;
+            current = next;
+            nearest_neighbor_walk.push(current.fix().index());
+        }
+    }
+
+    let mut steps = Vec::new();
+    let mut e0 = current.out_edge().expect("vertex without out edge");
+    let mut e0_query = e0.side_query(target);
+    let mut rotate_ccw = e0_query.is_on_left_side_or_on_line();
+
+    for _ in 0..triangulation.num_directed_edges() {
+        steps.push(OracleLocateStep {
+            edge: e0.fix().index(),
+            face: None,
+        });
+
+        let [from, to] = e0.vertices();
+        if from.position() == target {
+            return (
+                nearest_neighbor_walk,
+                steps,
+                Some(PositionInTriangulation::OnVertex(from.fix())),
+            );
+        }
+        if to.position() == target {
+            return (
+                nearest_neighbor_walk,
+                steps,
+                Some(PositionInTriangulation::OnVertex(to.fix())),
+            );
+        }
+
+        if e0_query.is_on_line() {
+            if e0.is_outer_edge() {
+                e0 = e0.rev();
+            }
+            e0 = e0.prev();
+            e0_query = e0.side_query(target);
+            rotate_ccw = e0_query.is_on_left_side_or_on_line();
+            continue;
+        }
+
+        let e1 = if rotate_ccw { e0 } else { e0.rev() };
+        let Some(face) = e1.face().as_inner() else {
+            return (
+                nearest_neighbor_walk,
+                steps,
+                Some(PositionInTriangulation::OutsideOfConvexHull(e1.fix())),
+            );
+        };
+        if let Some(step) = steps.last_mut() {
+            step.face = Some(face.index());
+        }
+
+        let rotated = if rotate_ccw { e0.ccw() } else { e0.cw() };
+        let rotated_query = rotated.side_query(target);
+        if rotated_query.is_on_line() || rotated_query.is_on_left_side() == rotate_ccw {
+            e0 = rotated;
+            e0_query = rotated_query;
+            continue;
+        }
+
+        let e2 = if rotate_ccw { e1.next() } else { e1.prev() };
+        let e2_query = e2.side_query(target);
+        if e2_query.is_on_line() {
+            return (
+                nearest_neighbor_walk,
+                steps,
+                Some(PositionInTriangulation::OnEdge(e2.fix())),
+            );
+        }
+        if e2_query.is_on_left_side() {
+            return (
+                nearest_neighbor_walk,
+                steps,
+                Some(PositionInTriangulation::OnFace(face.fix())),
+            );
+        }
+
+        e0 = e2.rev();
+        e0_query = e2_query.reversed();
+        if !e0.is_outer_edge() {
+            e0 = e0.prev();
+            e0_query = e0.side_query(target);
+        }
+        rotate_ccw = e0_query.is_on_left_side_or_on_line();
+    }
+
+    (nearest_neighbor_walk, steps, None)
+}
+
+/// Inner triangles keyed by their directed edges: `(a, b) -> c` for every CCW triangle `(a, b, c)`.
+#[derive(Debug, Default)]
+struct ApexMap(HashMap<(usize, usize), usize>);
+
+impl ApexMap {
+    fn from_triangulation(triangulation: &Delaunay) -> Self {
+        let mut map = ApexMap::default();
+        for face in triangulation.inner_faces() {
+            map.add(face.vertices().map(|v| v.fix().index()));
+        }
+        map
+    }
+
+    fn add(&mut self, [a, b, c]: [usize; 3]) {
+        self.0.insert((a, b), c);
+        self.0.insert((b, c), a);
+        self.0.insert((c, a), b);
+    }
+
+    fn remove(&mut self, [a, b, c]: [usize; 3]) {
+        self.0.remove(&(a, b));
+        self.0.remove(&(b, c));
+        self.0.remove(&(c, a));
+    }
+
+    fn apex(&self, a: usize, b: usize) -> Option<usize> {
+        self.0.get(&(a, b)).copied()
+    }
+
+    /// All triangles, rotated to start at their smallest vertex.
+    fn triangles(&self) -> BTreeSet<[usize; 3]> {
+        self.0
+            .iter()
+            .map(|(&(a, b), &c)| {
+                let t = [a, b, c];
+                let min = (0..3).min_by_key(|&i| t[i]).unwrap_or(0);
+                [t[min], t[(min + 1) % 3], t[(min + 2) % 3]]
+            })
+            .collect()
+    }
+}
+
+/// Replays spade's insertion of one new vertex on the triangles of `before`.
+struct Replay<'a> {
+    before: &'a Delaunay,
+    positions: Vec<Point2<f64>>,
+    new_vertex: usize,
+    apex: ApexMap,
+    flips: Vec<([usize; 2], [usize; 2])>,
+}
+
+impl<'a> Replay<'a> {
+    fn new(before: &'a Delaunay, position: Point2<f64>) -> Self {
+        let mut positions: Vec<_> = before.vertices().map(|v| v.position()).collect();
+        positions.push(position);
+        Replay {
+            before,
+            new_vertex: before.num_vertices(),
+            positions,
+            apex: ApexMap::from_triangulation(before),
+            flips: Vec::new(),
+        }
+    }
+
+    fn insert(&mut self, position: PositionInTriangulation) {
+        let before = self.before;
+        let v = self.new_vertex;
+        let target = self.positions[v];
+
+        if before.num_vertices() < 2 {
+            return;
+        }
+
+        if before.all_vertices_on_line() {
+            // Only a vertex off the line creates faces; it is attached like a hull insertion.
+            let Some(edge) = before.directed_edges().next() else {
+                return;
+            };
+            let query = edge.side_query(target);
+            if query.is_on_left_side() {
+                self.insert_outside_of_convex_hull(edge.fix());
+            } else if query.is_on_right_side() {
+                self.insert_outside_of_convex_hull(edge.fix().rev());
+            }
+            return;
+        }
+
+        match position {
+            PositionInTriangulation::OnFace(face) => {
+                let e0 = before.face(face).adjacent_edge();
+                let [a, b] = e0.vertices().map(|v| v.fix().index());
+                let c = e0.next().to().fix().index();
+                self.apex.remove([a, b, c]);
+                self.apex.add([a, b, v]);
+                self.apex.add([b, c, v]);
+                self.apex.add([c, a, v]);
+                self.legalize_vertex(&[(b, c), (c, a), (a, b)]);
+            }
+            PositionInTriangulation::OnEdge(edge) => {
+                let edge = before.directed_edge(edge);
+                if edge.is_outer_edge() || edge.rev().is_outer_edge() {
+                    // Hull edge: only the inner side is split.
+                    let inner = if edge.is_outer_edge() {
+                        edge.rev()
+                    } else {
+                        edge
+                    };
+                    let [a, b] = inner.vertices().map(|v| v.fix().index());
+                    let c = inner.next().to().fix().index();
+                    self.apex.remove([a, b, c]);
+                    self.apex.add([a, v, c]);
+                    self.apex.add([v, b, c]);
+                    self.legalize_vertex(&[(b, c), (c, a)]);
+                } else {
+                    let [a, b] = edge.vertices().map(|v| v.fix().index());
+                    let c = edge.next().to().fix().index();
+                    let d = edge.rev().next().to().fix().index();
+                    self.apex.remove([a, b, c]);
+                    self.apex.remove([b, a, d]);
+                    self.apex.add([a, v, c]);
+                    self.apex.add([v, b, c]);
+                    self.apex.add([b, v, d]);
+                    self.apex.add([v, a, d]);
+                    self.legalize_vertex(&[(a, d), (d, b), (b, c), (c, a)]);
+                }
+            }
+            PositionInTriangulation::OutsideOfConvexHull(edge) => {
+                self.insert_outside_of_convex_hull(edge);
+            }
+            PositionInTriangulation::OnVertex(_) | PositionInTriangulation::NoTriangulation => {}
+        }
+    }
+
+    /// Mirrors `insert_outside_of_convex_hull`: attach to the located hull edge, then walk the
+    /// hull backwards and forwards while the new vertex sees the next edge.
+    fn insert_outside_of_convex_hull(&mut self, hull_edge: FixedDirectedEdgeHandle) {
+        let target = self.positions[self.new_vertex];
+
+        let edge = self.before.directed_edge(hull_edge);
+        self.attach_hull_edge(edge.vertices().map(|v| v.fix().index()));
+
+        let mut current = edge.prev();
+        while current.side_query(target).is_on_left_side() {
+            self.attach_hull_edge(current.vertices().map(|v| v.fix().index()));
+            current = current.prev();
+        }
+
+        let mut current = edge.next();
+        while current.side_query(target).is_on_left_side() {
+            self.attach_hull_edge(current.vertices().map(|v| v.fix().index()));
+            current = current.next();
+        }
+    }
+
+    fn attach_hull_edge(&mut self, [a, b]: [usize; 2]) {
+        self.apex.add([a, b, self.new_vertex]);
+        self.legalize_edge((a, b));
+    }
+
+    /// Legalizes the edges opposite the new vertex, in the order of its out edges.
+    fn legalize_vertex(&mut self, edges: &[(usize, usize)]) {
+        for &edge in edges {
+            self.legalize_edge(edge);
+        }
+    }
+
+    /// Mirrors `legalize_edge`: the stack discipline decides the flip order.
+    fn legalize_edge(&mut self, edge: (usize, usize)) {
+        let mut stack = vec![edge];
+        while let Some((a, b)) = stack.pop() {
+            let (Some(far), Some(near)) = (self.apex.apex(b, a), self.apex.apex(a, b)) else {
+                continue;
+            };
+
+            if incircle(
+                self.positions[a],
+                self.positions[b],
+                self.positions[far],
+                self.positions[near],
+            ) < 0.0
+            {
+                stack.push((a, far));
+                stack.push((far, b));
+
+                self.apex.remove([a, b, near]);
+                self.apex.remove([b, a, far]);
+                self.apex.add([far, b, near]);
+                self.apex.add([far, near, a]);
+                self.flips.push(([a, b], [far, near]));
+            }
+        }
+    }
+}
+
+fn incircle(a: Point2<f64>, b: Point2<f64>, c: Point2<f64>, d: Point2<f64>) -> f64 {
+    let coord = |p: Point2<f64>| robust::Coord { x: p.x, y: p.y };
+    robust::incircle(coord(a), coord(b), coord(c), coord(d))
+}
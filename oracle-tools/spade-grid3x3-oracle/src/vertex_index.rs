@@ -0,0 +1,127 @@
+//! Maps spade vertex handles back to positions in the oracle input.
+//!
+//! Spade appends a new vertex for every successful insertion, so a fresh vertex handle's index
+//! is the number of vertices before the call. Inserting a position that already exists updates
+//! the existing vertex and returns its handle instead; those inputs are recorded as duplicates
+//! and resolved to the input index that created the vertex. Spade validates a position before
+//! touching the triangulation, so a rejected input leaves it unchanged and insertion carries on
+//! with the next point.
+
+use spade::handles::FixedVertexHandle;
+use spade::{InsertionError, Point2, Triangulation};
+
+use crate::model::{
+    OracleDuplicate, OracleInsertion, OracleInsertionStatus, OraclePoint, OracleRejection,
+    OracleRejectionReason,
+};
+
+#[derive(Debug, Default)]
+pub struct VertexIndex {
+    input_by_vertex: Vec<usize>,
+    duplicates: Vec<OracleDuplicate>,
+    rejected: Vec<OracleRejection>,
+    insertions: Vec<OracleInsertion>,
+    steiner_count: usize,
+}
+
+impl VertexIndex {
+    /// Records that input `input_index` was inserted and resolved to `handle`.
+    pub fn record(&mut self, input_index: usize, handle: FixedVertexHandle) {
+        let status = if handle.index() == self.input_by_vertex.len() {
+            self.input_by_vertex.push(input_index);
+            OracleInsertionStatus::Inserted {
+                vertex: handle.index(),
+            }
+        } else {
+            let merged_into = self.input_index(handle);
+            self.duplicates.push(OracleDuplicate {
+                index: input_index,
+                merged_into,
+            });
+            OracleInsertionStatus::Duplicate { merged_into }
+        };
+        self.insertions.push(OracleInsertion {
+            index: input_index,
+            status,
+        });
+    }
+
+    /// Records that spade refused to insert input `input_index`.
+    pub fn record_rejection(&mut self, input_index: usize, error: InsertionError) {
+        let reason = OracleRejectionReason::from(error);
+        let status = match reason {
+            OracleRejectionReason::Nan => OracleInsertionStatus::Nan,
+            OracleRejectionReason::TooSmall => OracleInsertionStatus::TooSmall,
+            OracleRejectionReason::TooLarge => OracleInsertionStatus::TooLarge,
+        };
+        self.rejected.push(OracleRejection {
+            index: input_index,
+            reason,
+        });
+        self.insertions.push(OracleInsertion {
+            index: input_index,
+            status,
+        });
+    }
+
+    /// Records a vertex that spade created on its own, such as a constraint-split Steiner vertex,
+    /// and returns the index assigned to it: the next one after all inputs and earlier Steiner
+    /// vertices. Only valid once every input has been recorded.
+    pub fn record_steiner(&mut self, handle: FixedVertexHandle) -> usize {
+        debug_assert_eq!(handle.index(), self.input_by_vertex.len());
+        let index = self.insertions.len() + self.steiner_count;
+        self.steiner_count += 1;
+        self.input_by_vertex.push(index);
+        index
+    }
+
+    /// Number of vertices recorded so far, inputs and Steiner vertices alike.
+    pub fn vertex_count(&self) -> usize {
+        self.input_by_vertex.len()
+    }
+
+    /// Returns the input index of the point that created `handle`.
+    pub fn input_index(&self, handle: FixedVertexHandle) -> usize {
+        self.input_by_vertex[handle.index()]
+    }
+
+    /// Returns the vertex input `input_index` resolved to, or `None` if it was rejected. Only
+    /// valid once every input up to `input_index` has been recorded in order.
+    pub fn vertex(&self, input_index: usize) -> Option<FixedVertexHandle> {
+        match self.insertions[input_index].status {
+            OracleInsertionStatus::Inserted { vertex } => {
+                Some(FixedVertexHandle::from_index(vertex))
+            }
+            OracleInsertionStatus::Duplicate { merged_into } => self.vertex(merged_into),
+            _ => None,
+        }
+    }
+
+    pub fn duplicates(&self) -> &[OracleDuplicate] {
+        &self.duplicates
+    }
+
+    pub fn rejected(&self) -> &[OracleRejection] {
+        &self.rejected
+    }
+
+    /// One entry per recorded input, in the order they were recorded.
+    pub fn insertions(&self) -> &[OracleInsertion] {
+        &self.insertions
+    }
+}
+
+/// Inserts `points` one by one in input order, skipping the ones spade rejects.
+pub fn insert_points<T>(triangulation: &mut T, points: &[OraclePoint]) -> VertexIndex
+where
+    T: Triangulation<Vertex = Point2<f64>>,
+{
+    let mut index = VertexIndex::default();
+    for (i, p) in points.iter().enumerate() {
+        match triangulation.insert(Point2::new(p.x, p.y)) {
+            Ok(handle) => index.record(i, handle),
+            Err(error) => index.record_rejection(i, error),
+        }
+    }
+    index
+}
@@ -0,0 +1,232 @@
+//! Voronoi cells read off spade's `voronoi_faces()`.
+//!
+//! Every vertex of the triangulation is the generator of one cell, reported under the input
+//! index that created it. A cell's polygon is the circumcenters of the faces around its
+//! generator, in the counterclockwise order of the generator's out edges. A cell on the convex
+//! hull is unbounded: one of those faces is the outer face, so the polygon is the open chain
+//! starting after it, and the two hull edges at the generator add a ray at either end,
+//! perpendicular to the hull edge and pointing away from the hull.
+//!
+//! Cocircular generators share a circumcenter, so polygons keep spade's zero-length Voronoi
+//! edges as repeated points.
+//!
+//! `edges` lists every undirected Voronoi edge once, classified by its two `VoronoiVertex`
+//! ends: two `Inner` ends make a segment between circumcenters, one `Outer` end a ray from the
+//! other end's circumcenter, and two `Outer` ends (all vertices on a line) the whole bisector.
+
+use serde::Serialize;
+use spade::handles::{DirectedVoronoiEdge, VoronoiVertex};
+use spade::{Point2, Triangulation};
+
+use crate::model::{OraclePoint, OracleTriangulationOutput};
+use crate::triangle_output;
+use crate::vertex_index::VertexIndex;
+
+/// A superset of `OracleTriangulationOutput` and of the .NET `OracleVoronoiOutput`, so both
+/// `DeserializeTriangulation` and `DeserializeVoronoi` read it.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OracleVoronoiOutput {
+    #[serde(flatten)]
+    pub triangulation: OracleTriangulationOutput,
+    /// One cell per vertex, in vertex handle order.
+    pub cells: Vec<OracleVoronoiCell>,
+    /// One entry per undirected Voronoi edge, in undirected edge handle order.
+    pub edges: Vec<OracleVoronoiEdge>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OracleVoronoiCell {
+    pub generator_index: usize,
+    /// Circumcenters in counterclockwise order; an open chain between the two rays when the cell
+    /// is unbounded.
+    pub polygon: Vec<OraclePoint>,
+    /// Generators of the adjacent cells, by input index, in counterclockwise order.
+    pub neighbors: Vec<usize>,
+    pub bounded: bool,
+    /// Unbounded cells only: the ray leaving the first polygon point, then the ray leaving the
+    /// last one. When all vertices are on a line the cell is a strip or half-plane with no
+    /// polygon, and each bisector is given as two opposite rays from the edge's midpoint.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub rays: Vec<OracleVoronoiRay>,
+}
+
+/// A half-line from `origin` in `direction`, which is the hull edge rotated by 90 degrees and
+/// has the same length.
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OracleVoronoiRay {
+    pub origin: OraclePoint,
+    pub direction: OraclePoint,
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OracleVoronoiEdge {
+    /// The dual Delaunay edge by input index: the generator of the cell on the edge's left, then
+    /// the one on its right, looking from `from` to `to` or along `direction`.
+    pub generators: [usize; 2],
+    #[serde(flatten)]
+    pub geometry: OracleVoronoiEdgeGeometry,
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(
+    tag = "kind",
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase"
+)]
+pub enum OracleVoronoiEdgeGeometry {
+    /// Both ends are `Inner`: the circumcenters of the two faces sharing the dual edge.
+    Segment { from: OraclePoint, to: OraclePoint },
+    /// One end is `Outer`: a hull edge's dual, from the inner face's circumcenter away from the
+    /// hull.
+    Ray {
+        origin: OraclePoint,
+        direction: OraclePoint,
+    },
+    /// Both ends are `Outer`: the perpendicular bisector of the dual edge, through its midpoint.
+    Line {
+        origin: OraclePoint,
+        direction: OraclePoint,
+    },
+}
+
+pub fn voronoi_output<T>(
+    triangulation: &T,
+    index: &VertexIndex,
+    points: &[OraclePoint],
+) -> OracleVoronoiOutput
+where
+    T: Triangulation<Vertex = Point2<f64>>,
+{
+    let on_line = triangulation.all_vertices_on_line();
+    let cells = triangulation
+        .voronoi_faces()
+        .map(|face| {
+            let generator = face.as_delaunay_vertex();
+            let mut edges: Vec<_> = generator.out_edges().collect();
+
+            let mut rays = Vec::new();
+            let mut polygon = Vec::new();
+            if on_line {
+                for e in &edges {
+                    let [from, to] = e.positions();
+                    let origin = point(Point2::new((from.x + to.x) / 2.0, (from.y + to.y) / 2.0));
+                    let direction = left_normal(from, to);
+                    rays.push(OracleVoronoiRay { origin, direction });
+                    rays.push(OracleVoronoiRay {
+                        origin,
+                        direction: OraclePoint {
+                            x: -direction.x,
+                            y: -direction.y,
+                        },
+                    });
+                }
+            } else {
+                // Start right after the outer face, if any, so the polygon is one open chain.
+                let outer = edges.iter().position(|e| e.face().is_outer());
+                if let Some(k) = outer {
+                    edges.rotate_left(k + 1);
+                }
+                polygon = edges
+                    .iter()
+                    .filter_map(|e| e.face().as_inner())
+                    .map(|f| point(f.circumcenter()))
+                    .collect();
+                if let (Some(first), Some(last)) = (edges.first(), edges.last()) {
+                    if outer.is_some() {
+                        rays.push(OracleVoronoiRay {
+                            origin: polygon[0],
+                            direction: left_normal(first.to().position(), first.from().position()),
+                        });
+                        rays.push(OracleVoronoiRay {
+                            origin: polygon[polygon.len() - 1],
+                            direction: left_normal(last.from().position(), last.to().position()),
+                        });
+                    }
+                }
+            }
+
+            OracleVoronoiCell {
+                generator_index: index.input_index(generator.fix()),
+                polygon,
+                neighbors: edges
+                    .iter()
+                    .map(|e| index.input_index(e.to().fix()))
+                    .collect(),
+                bounded: !on_line && rays.is_empty() && !edges.is_empty(),
+                rays,
+            }
+        })
+        .collect();
+
+    let edges = triangulation
+        .undirected_voronoi_edges()
+        .map(|edge| voronoi_edge(edge.as_directed(), index))
+        .collect();
+
+    OracleVoronoiOutput {
+        triangulation: triangle_output(triangulation, index, points),
+        cells,
+        edges,
+    }
+}
+
+/// Classifies `edge` by its ends; rays are reported from their inner end whichever way `edge`
+/// points.
+pub fn voronoi_edge<DE, UE, F>(
+    mut edge: DirectedVoronoiEdge<'_, Point2<f64>, DE, UE, F>,
+    index: &VertexIndex,
+) -> OracleVoronoiEdge {
+    let geometry = match [edge.from(), edge.to()] {
+        [VoronoiVertex::Inner(from), VoronoiVertex::Inner(to)] => {
+            OracleVoronoiEdgeGeometry::Segment {
+                from: point(from.circumcenter()),
+                to: point(to.circumcenter()),
+            }
+        }
+        [VoronoiVertex::Outer(_), VoronoiVertex::Outer(_)] => {
+            let [from, to] = edge.as_delaunay_edge().positions();
+            OracleVoronoiEdgeGeometry::Line {
+                origin: point(Point2::new((from.x + to.x) / 2.0, (from.y + to.y) / 2.0)),
+                direction: left_normal(to, from),
+            }
+        }
+        [inner, _] => {
+            // Direct the edge from the inner end towards the outer one.
+            let origin = match inner {
+                VoronoiVertex::Inner(face) => face.circumcenter(),
+                VoronoiVertex::Outer(_) => {
+                    edge = edge.rev();
+                    edge.from().position().expect("inner Voronoi vertex")
+                }
+            };
+            let [from, to] = edge.as_delaunay_edge().positions();
+            OracleVoronoiEdgeGeometry::Ray {
+                origin: point(origin),
+                direction: left_normal(to, from),
+            }
+        }
+    };
+    // The dual edge crosses from left to right, so its head is the left generator.
+    let [right, left] = edge.as_delaunay_edge().vertices();
+    OracleVoronoiEdge {
+        generators: [left, right].map(|v| index.input_index(v.fix())),
+        geometry,
+    }
+}
+
+/// The edge from `from` to `to` rotated counterclockwise by 90 degrees; points away from the
+/// hull for a hull edge with the outer face on its left.
+pub fn left_normal(from: Point2<f64>, to: Point2<f64>) -> OraclePoint {
+    OraclePoint {
+        x: from.y - to.y,
+        y: to.x - from.x,
+    }
+}
+
+fn point(p: Point2<f64>) -> OraclePoint {
+    OraclePoint { x: p.x, y: p.y }
+}